@@ -0,0 +1,18 @@
+// https://github.com/archid-protocol/archid-registry/blob/main/src/msg.rs
+
+use cosmwasm_schema::cw_serde;
+
+/// The subset of the ArchID registry queries used to verify player names.
+#[cw_serde]
+pub enum QueryMsg {
+    /// Resolves a domain such as `hello.arch` to the address it points to.
+    ResolveRecord { name: String },
+}
+
+#[cw_serde]
+pub struct ResolveRecordResponse {
+    /// Address the domain resolves to, if one is set.
+    pub address: Option<String>,
+    /// Unix timestamp in seconds after which the domain has expired.
+    pub expiration: u64,
+}
@@ -0,0 +1,96 @@
+use crate::random::Rng;
+use crate::state::{Move, MoveEffect, Stats};
+
+// damage dealt by a move of `BASE_POWER` between pokemon with equal attack and defense
+const BASE_DAMAGE: i32 = 20;
+const BASE_POWER: i32 = 40;
+
+/// A move a fighter knows, with the PP it has left.
+pub struct BattleMove {
+    pub details: Move,
+    pub pp: u32,
+}
+
+/// A pokemon taking part in a battle.
+pub struct Fighter {
+    pub stats: Stats,
+    pub types: Vec<String>,
+    pub health: i32,
+    /// Cap for healing moves.
+    pub max_health: i32,
+    /// Once none of them has PP left, the fighter struggles with a plain hit.
+    pub moves: Vec<BattleMove>,
+}
+
+impl Fighter {
+    fn hit_by(&mut self, attacker: &Stats, power: i32) {
+        let damage =
+            BASE_DAMAGE * power * attacker.attack / (BASE_POWER * self.stats.defense.max(1));
+        self.health = (self.health - damage.max(1)).max(0);
+    }
+
+    /// Uses a random move with PP left, spending one PP even on a miss. Returns whether the
+    /// target flinches.
+    fn strike(&mut self, target: &mut Fighter, rng: &mut Rng) -> bool {
+        let usable: Vec<_> = (0..self.moves.len())
+            .filter(|&index| self.moves[index].pp > 0)
+            .collect();
+        if usable.is_empty() {
+            target.hit_by(&self.stats, BASE_POWER);
+            return false;
+        }
+        let chosen = &mut self.moves[usable[rng.below(usable.len() as u64) as usize]];
+        chosen.pp -= 1;
+        let details = chosen.details.clone();
+        if rng.below(100) >= details.accuracy.into() {
+            return false;
+        }
+        if details.power > 0 {
+            let mut power = details.power as i32;
+            // moves of the user's own type hit harder
+            if self.types.contains(&details.kind) {
+                power = power * 3 / 2;
+            }
+            target.hit_by(&self.stats, power);
+        }
+        match details.effect {
+            Some(MoveEffect::Heal { amount }) => {
+                self.health = (self.health + amount).min(self.max_health);
+            }
+            // recoil alone never knocks the user out
+            Some(MoveEffect::Recoil { amount }) => self.health = (self.health - amount).max(1),
+            Some(MoveEffect::Flinch {}) => return true,
+            None => {}
+        }
+        false
+    }
+}
+
+/// Takes turns until one side faints, the faster pokemon striking first and `a` winning ties.
+/// Returns whether `a` is left standing.
+pub fn duel(a: &mut Fighter, b: &mut Fighter, rng: &mut Rng) -> bool {
+    let mut a_turn = a.stats.speed >= b.stats.speed;
+    let (mut attacker, mut target) = if a_turn { (a, b) } else { (b, a) };
+    let mut flinched = false;
+    while attacker.health > 0 && target.health > 0 {
+        flinched = !flinched && attacker.strike(target, rng);
+        std::mem::swap(&mut attacker, &mut target);
+        a_turn = !a_turn;
+    }
+    let a = if a_turn { attacker } else { target };
+    a.health > 0
+}
+
+/// Sends out both teams in order, the winner of each duel staying in with the health it has
+/// left. The challengers win once every defender has fainted.
+pub fn battle(challengers: &mut [Fighter], defenders: &mut [Fighter], rng: &mut Rng) -> bool {
+    let (mut challenger, mut defender) = (0, 0);
+    while challenger < challengers.len() && defender < defenders.len() {
+        if duel(&mut challengers[challenger], &mut defenders[defender], rng) {
+            defender += 1;
+        } else {
+            challenger += 1;
+        }
+    }
+    defender == defenders.len()
+}
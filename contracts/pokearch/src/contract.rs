@@ -1,33 +1,157 @@
-use andromeda_non_fungible_tokens::cw721::ExecuteMsg as Cw721ExecuteMsg;
+use andromeda_non_fungible_tokens::cw721::{
+    ExecuteMsg as Cw721ExecuteMsg, InstantiateMsg as Cw721InstantiateMsg,
+};
+use andromeda_std::amp::AndrAddr;
+use archway_bindings::{ArchwayMsg, ArchwayQuery, ArchwayResult};
 
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_json_binary, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
-    WasmMsg,
+    from_json, to_json_binary, Addr, BankMsg, Binary, Coin, Coins, CosmosMsg, Decimal, Deps,
+    DepsMut, Empty, Env, Event, MessageInfo, Reply, Response, StdResult, Storage, SubMsg, WasmMsg,
 };
 use cw2::set_contract_version;
 
-use crate::cwfees::{CwGrant, MsgRegisterAsGranter, SudoMsg};
+use crate::cwfees::{CwGrant, MsgExecuteContract, MsgRegisterAsGranter, SudoMsg};
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{ALLOWED_ADDRESSES, NFT_CONTRACT, OWNER, PLAYERS};
+use crate::msg::{ExecuteMsg, InstantiateMsg, NewCollection, PauseStatusResponse, QueryMsg};
+use crate::state::{
+    BreedingConfig, CheckInSchedule, Feature, Genes, GrantPolicy, ItemKind, Items, PokemonCenter,
+    Premium, RewardsLedger, RewardsSplit, Variant, VariantOdds, ALLOWED_ADDRESSES, BREEDING,
+    BREEDING_COOLDOWNS, CHECK_IN, COLLECTIONS, DISABLED_FEATURES, EGGS, EGG_COUNT, GAS_TANK,
+    GUARDIANS, GUILDS, GUILD_MEMBERS, GYMS, LINEAGE, MOVES, NFT_CONTRACT, OWNER, PAUSED, PLAYERS,
+    PLAYER_CAUGHT, PLAYER_GUILDS, POKEMON_CENTER, PREMIUM, PRIZE_POOL, REGIONS, REWARDS_LEDGER,
+    REWARDS_SPLIT, SEASON, SPECIES_CAUGHT, VARIANTS_CAUGHT, VARIANT_ODDS,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:pokearch";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// health of a fully healed pokemon; a revive brings a fainted pokemon back to half of it
+const MAX_HEALTH: i32 = 100;
+
+// pokemon a player can take into a gym battle
+const MAX_PARTY: usize = 6;
+
+// largest guild a player can found
+const MAX_GUILD_MEMBERS: u32 = 50;
+
+// time between free Pokemon Center visits, unless changed by the owner
+const DEFAULT_CENTER_COOLDOWN: u64 = 60 * 60;
+
+// time a wild pokemon has to be left alone before exploring again, unless a ticket is spent
+const EXPLORE_COOLDOWN: u64 = 5 * 60;
+
+// time between berry collections, which also caps how fast eggs are walked
+const FORAGE_COOLDOWN: u64 = 3 * 60;
+
+// length of a check-in day, counted from the unix epoch
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+// experience granted to the winning pokemon of a battle, and needed per level
+const XP_PER_WIN: i32 = 25;
+const XP_PER_LEVEL: i32 = 100;
+
+/// Individual values range from zero up to this.
+const MAX_IV: i32 = 31;
+
+const MAX_MOVES: usize = 4;
+
+const WITHDRAW_REWARDS_REPLY_ID: u64 = 1;
+const MINT_REPLY_ID: u64 = 2;
+const CREATE_COLLECTION_REPLY_ID: u64 = 3;
+
+// leaderboard pagination
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+/// Every execute path emits exactly one event named `pokearch.<action>`, which the chain
+/// reports to indexers as `wasm-pokearch.<action>`.
+fn event(action: &str) -> Event {
+    Event::new(format!("pokearch.{action}"))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    deps: DepsMut,
+    deps: DepsMut<ArchwayQuery>,
     env: Env,
     info: MessageInfo,
-    _msg: InstantiateMsg,
-) -> Result<Response, ContractError> {
+    msg: InstantiateMsg,
+) -> ArchwayResult<ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     let contract_address = env.clone().contract.address;
 
     OWNER.save(deps.storage, &info.sender)?;
+    SEASON.save(deps.storage, &0)?;
+    POKEMON_CENTER.save(
+        deps.storage,
+        &PokemonCenter {
+            cooldown: DEFAULT_CENTER_COOLDOWN,
+            fee: None,
+        },
+    )?;
+    // until the owner decides otherwise, all developer rewards go to fee grants
+    REWARDS_SPLIT.save(
+        deps.storage,
+        &RewardsSplit {
+            gas_tank: Decimal::one(),
+            prize_pool: Decimal::zero(),
+            treasury: info.sender.clone(),
+        },
+    )?;
+    // a week of growing rewards ending in a ticket; a missed day costs five berries
+    let items = |kind, amount| Items { kind, amount };
+    CHECK_IN.save(
+        deps.storage,
+        &CheckInSchedule {
+            rewards: vec![
+                items(ItemKind::Berry, 1),
+                items(ItemKind::Berry, 2),
+                items(ItemKind::Berry, 3),
+                items(ItemKind::Potion, 1),
+                items(ItemKind::Potion, 2),
+                items(ItemKind::Potion, 3),
+                items(ItemKind::Ticket, 1),
+            ],
+            protection: Some(items(ItemKind::Berry, 5)),
+        },
+    )?;
+    // a pokemon breeds once a day; eggs hatch after about an hour or twenty berries
+    BREEDING.save(
+        deps.storage,
+        &BreedingConfig {
+            cooldown: SECONDS_PER_DAY,
+            hatch_blocks: 600,
+            hatch_steps: 20,
+        },
+    )?;
+    EGG_COUNT.save(deps.storage, &0)?;
+    VARIANT_ODDS.save(
+        deps.storage,
+        &VariantOdds {
+            shiny: 4096,
+            alternate: 512,
+        },
+    )?;
+    REWARDS_LEDGER.save(deps.storage, &RewardsLedger::default())?;
+    // fee grants are only paid while the tank holds enough, so it starts out with whatever
+    // the contract is instantiated with
+    let mut gas_tank = vec![];
+    for coin in info.funds.iter().cloned() {
+        add_coin(&mut gas_tank, coin)?;
+    }
+    GAS_TANK.save(deps.storage, &gas_tank)?;
+    PRIZE_POOL.save(deps.storage, &vec![])?;
+    PREMIUM.save(
+        deps.storage,
+        &Premium {
+            flat_fee: None,
+            grant_policy: GrantPolicy::Sponsor,
+        },
+    )?;
+    PAUSED.save(deps.storage, &false)?;
+    DISABLED_FEATURES.save(deps.storage, &vec![])?;
     ALLOWED_ADDRESSES.save(deps.storage, info.sender.clone(), &Empty {})?;
 
     let contract_address = contract_address.to_string();
@@ -39,482 +163,6357 @@ pub fn instantiate(
         value: Binary::from(prost::Message::encode_to_vec(&regsiter_msg)),
     };
 
-    Ok(Response::new()
+    let mut response = Response::new()
         .add_attribute("method", "instantiate")
-        .add_attribute("owner", info.sender)
+        .add_attribute("owner", info.sender.clone())
         .add_attribute("action", "register")
-        .add_message(register_stargate_msg))
+        .add_message(register_stargate_msg);
+    if let Some(collection) = msg.collection {
+        response = response.add_submessage(execute::create_collection_msg(
+            deps.storage,
+            &env,
+            &info.sender,
+            collection,
+        )?);
+    }
+    Ok(response)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
-    deps: DepsMut,
+    deps: DepsMut<ArchwayQuery>,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
-) -> Result<Response, ContractError> {
+) -> ArchwayResult<ContractError> {
+    if msg.is_pausable() {
+        execute::ensure_enabled(deps.storage, msg.feature())?;
+    }
     match msg {
-        ExecuteMsg::RemoveAllowance(addr) => {
-            ALLOWED_ADDRESSES.remove(deps.storage, deps.api.addr_validate(&addr)?);
-            Ok(Response::default())
-        }
-        ExecuteMsg::AddAllowance(addr) => {
-            ALLOWED_ADDRESSES.save(
-                deps.storage,
-                deps.api.addr_validate(&addr)?,
-                &Empty::default(),
-            )?;
-            Ok(Response::default())
+        ExecuteMsg::RemoveAllowance(addr) => execute::remove_allowance(deps, info, addr),
+        ExecuteMsg::AddAllowance(addr) => execute::add_allowance(deps, info, addr),
+        ExecuteMsg::SetNFTContract { addr } => execute::set_nft_address(deps, info, addr),
+        ExecuteMsg::CreateCollection(collection) => {
+            execute::create_collection(deps, info, env, collection)
         }
-        ExecuteMsg::SetNFTContract { addr, token_uri } => {
-            execute::set_nft_address(deps, info, env, addr, token_uri)
-        }
-        ExecuteMsg::Register { id } => execute::register(deps, id),
+        ExecuteMsg::SetCollection { id, addr } => execute::set_collection(deps, info, id, addr),
+        ExecuteMsg::Register { id, starter } => execute::register(deps, info, env, id, starter),
         ExecuteMsg::CatchPokemon {
             id,
-            token_uri,
+            species,
             health,
             curr_pokemon,
-        } => execute::catch_pokemon(deps, info, env, id, token_uri, health, curr_pokemon),
-        ExecuteMsg::UpdateHealth { id, token_id } => execute::update_health(deps, id, token_id),
-        ExecuteMsg::CollectBerries { id } => execute::collect_berries(deps, id),
+        } => execute::catch_pokemon(deps, info, id, species, health, curr_pokemon),
+        ExecuteMsg::Revive { id, pokemon } => execute::revive(deps, info, id, pokemon),
+        ExecuteMsg::VisitPokemonCenter { id } => execute::visit_pokemon_center(deps, info, env, id),
+        ExecuteMsg::SetPokemonCenter { cooldown, fee } => {
+            execute::set_pokemon_center(deps, info, cooldown, fee)
+        }
+        ExecuteMsg::CheckIn { id, protect_streak } => {
+            execute::check_in(deps, info, env, id, protect_streak)
+        }
+        ExecuteMsg::SetCheckInSchedule { schedule } => {
+            execute::set_check_in_schedule(deps, info, schedule)
+        }
+        ExecuteMsg::CollectBerries { id } => execute::collect_berries(deps, info, env, id),
         ExecuteMsg::SetDefaultPokemon { id, pokemon } => {
-            execute::set_default_pokemon(deps, id, pokemon)
+            execute::set_default_pokemon(deps, info, id, pokemon)
+        }
+        ExecuteMsg::RecordBattle {
+            winner,
+            loser,
+            pokemon,
+        } => execute::record_battle(deps, info, winner, loser, pokemon),
+        ExecuteMsg::SetSpecies { species } => execute::set_species(deps, info, species),
+        ExecuteMsg::SetStarters { starters } => execute::set_starters(deps, info, starters),
+        ExecuteMsg::SetMetadataRenderer { base_uri } => {
+            execute::set_metadata_renderer(deps, info, base_uri)
+        }
+        ExecuteMsg::SetArchId { registry, token } => {
+            execute::set_archid(deps, info, registry, token)
+        }
+        ExecuteMsg::ReverifyPlayer { id } => execute::reverify_player(deps, info, env, id),
+        ExecuteMsg::SetRewardsMetadata {
+            owner_address,
+            rewards_address,
+        } => execute::set_rewards_metadata(deps, info, owner_address, rewards_address),
+        ExecuteMsg::WithdrawRewards {} => execute::withdraw_rewards(deps, info),
+        ExecuteMsg::SetRewardsSplit {
+            gas_tank,
+            prize_pool,
+            treasury,
+        } => execute::set_rewards_split(deps, info, gas_tank, prize_pool, treasury),
+        ExecuteMsg::FundGasTank {} => execute::fund_gas_tank(deps, info),
+        ExecuteMsg::AwardPrize { id, amount } => execute::award_prize(deps, info, id, amount),
+        ExecuteMsg::SetPremium {
+            flat_fee,
+            grant_policy,
+        } => execute::set_premium(deps, env, info, flat_fee, grant_policy),
+        ExecuteMsg::Pause {} => execute::set_paused(deps, info, true),
+        ExecuteMsg::Unpause {} => execute::set_paused(deps, info, false),
+        ExecuteMsg::SetFeature { feature, enabled } => {
+            execute::set_feature(deps, info, feature, enabled)
+        }
+        ExecuteMsg::AddGuardian(addr) => execute::add_guardian(deps, info, addr),
+        ExecuteMsg::RemoveGuardian(addr) => execute::remove_guardian(deps, info, addr),
+        ExecuteMsg::ResetSeason {} => execute::reset_season(deps, info),
+        ExecuteMsg::SetGym { id, gym } => execute::set_gym(deps, info, id, gym),
+        ExecuteMsg::ChallengeGym { id, gym, party } => {
+            execute::challenge_gym(deps, info, env, id, gym, party)
+        }
+        ExecuteMsg::CreateGuild {
+            id,
+            guild,
+            name,
+            max_members,
+        } => execute::create_guild(deps, info, id, guild, name, max_members),
+        ExecuteMsg::InviteToGuild { id, player } => {
+            execute::invite_to_guild(deps, info, id, player)
+        }
+        ExecuteMsg::JoinGuild { id, guild } => execute::join_guild(deps, info, id, guild),
+        ExecuteMsg::LeaveGuild { id } => execute::leave_guild(deps, info, id),
+        ExecuteMsg::KickFromGuild { id, player } => {
+            execute::kick_from_guild(deps, info, id, player)
+        }
+        ExecuteMsg::SetGuildRole { id, player, role } => {
+            execute::set_guild_role(deps, info, id, player, role)
+        }
+        ExecuteMsg::ContributeToGuild { id, items } => {
+            execute::contribute_to_guild(deps, info, id, items)
+        }
+        ExecuteMsg::GuildPayout {
+            id,
+            player,
+            items,
+            coins,
+        } => execute::guild_payout(deps, info, id, player, items, coins),
+        ExecuteMsg::Explore { id, region } => execute::explore(deps, info, env, id, region),
+        ExecuteMsg::SetRegion { id, region } => execute::set_region(deps, info, id, region),
+        ExecuteMsg::SetMove { id, details } => execute::set_move(deps, info, id, details),
+        ExecuteMsg::LearnMove {
+            id,
+            pokemon,
+            move_id,
+        } => execute::learn_move(deps, info, id, pokemon, move_id),
+        ExecuteMsg::ForgetMove {
+            id,
+            pokemon,
+            move_id,
+        } => execute::forget_move(deps, info, id, pokemon, move_id),
+        ExecuteMsg::SetVariantOdds { odds } => execute::set_variant_odds(deps, info, odds),
+        ExecuteMsg::Breed { id, first, second } => {
+            execute::breed(deps, info, env, id, first, second)
         }
+        ExecuteMsg::HatchEgg { id, egg } => execute::hatch_egg(deps, info, env, id, egg),
+        ExecuteMsg::SetBreedingConfig { config } => {
+            execute::set_breeding_config(deps, info, config)
+        }
+        ExecuteMsg::SetQuest { id, quest } => execute::set_quest(deps, info, id, quest),
+        ExecuteMsg::ClaimQuest { id, quest } => execute::claim_quest(deps, info, env, id, quest),
     }
 }
 
 pub mod execute {
     use andromeda_non_fungible_tokens::cw721::TokenExtension;
 
-    use crate::state::{Player, Pokemon, TOKEN};
+    use cosmwasm_std::{Order, StdError};
+    use cw721::{Cw721QueryMsg, NumTokensResponse, OwnerOfResponse};
+
+    use crate::archid::{QueryMsg as ArchIdQueryMsg, ResolveRecordResponse};
+    use crate::battle::{battle, BattleMove, Fighter};
+    use crate::random::Rng;
+    use crate::state::{
+        ArchId, Egg, Encounter, Guild, GuildRole, Gym, KnownMove, LeaderboardKind, Move, Nature,
+        Objective, Parent, PendingMint, Player, Pokemon, Quest, Region, Species, Stats, Treasury,
+        ARCHID, GUILD_INVITES, GUILD_QUEST_PROGRESS, METADATA_RENDERER, PENDING_COLLECTION,
+        PENDING_MINT, QUESTS, QUEST_PROGRESS, SPECIES, STARTERS, TOKENS,
+    };
 
     use super::*;
 
     pub fn set_nft_address(
-        deps: DepsMut,
+        deps: DepsMut<ArchwayQuery>,
         info: MessageInfo,
-        env: Env,
         addr: String,
-        token_uri: String,
-    ) -> Result<Response, ContractError> {
-        let owner = OWNER.load(deps.storage)?;
-        if info.sender.to_string() == owner.to_string() {
-            let mint: Cw721ExecuteMsg = Cw721ExecuteMsg::Mint {
-                token_id: 0.to_string(),
-                owner: env.contract.address.to_string(),
-                token_uri: Some(token_uri),
-                extension: TokenExtension {
-                    publisher: "PokeArch".to_string(),
-                },
-            };
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        let nft_address = deps.api.addr_validate(&addr)?;
+        NFT_CONTRACT.save(deps.storage, &nft_address)?;
+        Ok(Response::new().add_event(
+            event("set_nft_contract")
+                .add_attribute("sender", info.sender)
+                .add_attribute("contract", nft_address),
+        ))
+    }
 
-            let wasm_msg = WasmMsg::Execute {
-                contract_addr: addr.clone(),
-                msg: to_json_binary(&mint)?,
-                funds: Vec::new(),
-            };
-            NFT_CONTRACT.save(deps.storage, &deps.api.addr_validate(&addr.clone())?)?;
-            TOKEN.save(deps.storage, &(0 as i32))?;
-            Ok(Response::new().add_message(wasm_msg))
-        } else {
+    pub fn set_collection(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        id: String,
+        addr: String,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        let nft_address = deps.api.addr_validate(&addr)?;
+        COLLECTIONS.save(deps.storage, id.clone(), &nft_address)?;
+        Ok(Response::new().add_event(
+            event("set_collection")
+                .add_attribute("sender", info.sender)
+                .add_attribute("collection", id)
+                .add_attribute("contract", nft_address),
+        ))
+    }
+
+    pub fn create_collection(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        env: Env,
+        collection: NewCollection,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
             return Err(ContractError::Unauthorized {});
         }
+        let event = event("create_collection")
+            .add_attribute("sender", info.sender.clone())
+            .add_attribute("code_id", collection.code_id.to_string());
+        let msg = create_collection_msg(deps.storage, &env, &info.sender, collection)?;
+        Ok(Response::new().add_submessage(msg).add_event(event))
+    }
+
+    /// Instantiates a collection minted into by this contract and administered by `admin`.
+    pub fn create_collection_msg(
+        storage: &mut dyn Storage,
+        env: &Env,
+        admin: &Addr,
+        collection: NewCollection,
+    ) -> StdResult<SubMsg<ArchwayMsg>> {
+        PENDING_COLLECTION.save(storage, &collection.id)?;
+        let msg = Cw721InstantiateMsg {
+            name: collection.name.clone(),
+            symbol: collection.symbol,
+            minter: AndrAddr::from_string(env.contract.address.to_string()),
+            kernel_address: collection.kernel_address,
+            owner: Some(admin.to_string()),
+            modules: None,
+        };
+        let wasm_msg = WasmMsg::Instantiate {
+            admin: Some(admin.to_string()),
+            code_id: collection.code_id,
+            msg: to_json_binary(&msg)?,
+            funds: vec![],
+            label: collection.name,
+        };
+        Ok(SubMsg::reply_on_success(
+            wasm_msg,
+            CREATE_COLLECTION_REPLY_ID,
+        ))
     }
 
-    pub fn register(deps: DepsMut, id: String) -> Result<Response, ContractError> {
+    pub fn register(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        env: Env,
+        id: String,
+        starter: String,
+    ) -> ArchwayResult<ContractError> {
         if PLAYERS.has(deps.storage, id.clone()) {
             return Err(ContractError::Unauthorized {});
         }
-        let mut pokemon: Vec<Pokemon> = Vec::new();
-        pokemon.push(Pokemon {
-            token_id: 0,
-            index: 0,
-            health: 100,
-        });
+        if let Some(archid) = ARCHID.may_load(deps.storage)? {
+            verify_name(deps.as_ref(), &env, &archid, &id, &info.sender)?;
+        }
+        let Some(starters) = STARTERS.may_load(deps.storage)? else {
+            return Err(ContractError::NoStarters {});
+        };
+        if !starters.contains(&starter) {
+            return Err(ContractError::InvalidStarter { species: starter });
+        }
+        let species = SPECIES.load(deps.storage, starter.clone())?;
+        take_supply(deps.storage, &species, &id)?;
+        let nft_address = species_collection(deps.storage, &species)?;
+        let token = next_token_id(deps.as_ref(), &nft_address)?;
 
+        // the starter joins the roster once its mint is confirmed
         let player_data = Player {
             id: id.clone(),
+            address: info.sender.clone(),
             potions: 0,
             berries: 0,
             default_pokemon: 0,
-            pokemons: pokemon,
+            pokemons: vec![],
+            wins: 0,
+            revives: 0,
+            tickets: 0,
+            last_center_visit: None,
+            last_forage: None,
+            streak: 0,
+            last_check_in: None,
+            badges: vec![],
+            encounter: None,
         };
         PLAYERS.save(deps.storage, id.clone(), &player_data)?;
-        Ok(Response::default())
+        let pokemon = Pokemon {
+            collection: nft_address.clone(),
+            token_id: token,
+            species: starter.clone(),
+            index: 0,
+            health: MAX_HEALTH,
+            level: 1,
+            xp: 0,
+            original_trainer: id.clone(),
+            variant: None,
+            genes: roll_genes(&mut Rng::new(&env, &[id.as_bytes(), starter.as_bytes()])),
+            moves: starting_moves(deps.storage, &species, 1)?,
+        };
+        let mint = mint_msg(deps.storage, &info.sender, &id, pokemon, false)?;
+        Ok(Response::new().add_submessage(mint).add_event(
+            event("register")
+                .add_attribute("player", id)
+                .add_attribute("sender", info.sender)
+                .add_attribute("token_id", token.to_string())
+                .add_attribute("species", starter),
+        ))
     }
 
-    pub fn update_health(
-        deps: DepsMut,
+    /// Pays out the reward for the player's streak day. Days are counted in UTC from the block
+    /// time, so checking in right before and after midnight keeps a streak going.
+    pub fn check_in(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        env: Env,
         id: String,
-        token_id: i32,
-    ) -> Result<Response, ContractError> {
-        let mut player = PLAYERS.load(deps.storage, id.clone())?;
-        player.pokemons[token_id as usize].health = 100;
-        PLAYERS.save(deps.storage, id.clone(), &player)?;
-        Ok(Response::default())
+        protect_streak: bool,
+    ) -> ArchwayResult<ContractError> {
+        let schedule = CHECK_IN.load(deps.storage)?;
+        let mut player = load_owned_player(deps.storage, &id, &info.sender)?;
+        let today = env.block.time.seconds() / SECONDS_PER_DAY;
+        let missed = match player.last_check_in {
+            Some(last) => {
+                let last = last.seconds() / SECONDS_PER_DAY;
+                if last >= today {
+                    return Err(ContractError::AlreadyCheckedIn {
+                        next: (last + 1) * SECONDS_PER_DAY,
+                    });
+                }
+                today - last - 1
+            }
+            None => 0,
+        };
+
+        let mut event = event("check_in")
+            .add_attribute("player", id.clone())
+            .add_attribute("sender", info.sender);
+        if missed == 0 {
+            player.streak += 1;
+        } else if protect_streak {
+            let protection = schedule
+                .protection
+                .ok_or(ContractError::StreakNotProtected {})?;
+            let cost = protection
+                .amount
+                .saturating_mul(i32::try_from(missed).unwrap_or(i32::MAX));
+            let balance = player.items_mut(protection.kind);
+            if *balance < cost {
+                return Err(ContractError::StreakNotProtected {});
+            }
+            *balance -= cost;
+            player.streak += 1;
+            event = event
+                .add_attribute("protection", protection.kind.as_str())
+                .add_attribute("protection_delta", (-cost).to_string());
+        } else {
+            player.streak = 1;
+        }
+        player.last_check_in = Some(env.block.time);
+
+        let day = player.streak as usize;
+        let reward = schedule
+            .rewards
+            .get(day - 1)
+            .or(schedule.rewards.last())
+            .copied();
+        if let Some(reward) = reward {
+            *player.items_mut(reward.kind) += reward.amount;
+            event = event
+                .add_attribute("item", reward.kind.as_str())
+                .add_attribute("item_delta", reward.amount.to_string());
+        }
+        PLAYERS.save(deps.storage, id, &player)?;
+        Ok(Response::new().add_event(event.add_attribute("streak", player.streak.to_string())))
     }
 
-    pub fn collect_berries(deps: DepsMut, id: String) -> Result<Response, ContractError> {
-        let mut player = PLAYERS.load(deps.storage, id.clone())?;
+    pub fn set_check_in_schedule(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        schedule: CheckInSchedule,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        let mut items = schedule.rewards.iter().chain(&schedule.protection);
+        if items.any(|items| items.amount <= 0) {
+            return Err(ContractError::InvalidAmount {});
+        }
+        CHECK_IN.save(deps.storage, &schedule)?;
+        Ok(Response::new().add_event(
+            event("set_check_in_schedule")
+                .add_attribute("sender", info.sender)
+                .add_attribute("days", schedule.rewards.len().to_string()),
+        ))
+    }
+
+    pub fn collect_berries(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        env: Env,
+        id: String,
+    ) -> ArchwayResult<ContractError> {
+        let mut player = load_owned_player(deps.storage, &id, &info.sender)?;
+        if let Some(ready_at) = player
+            .last_forage
+            .map(|forage| forage.plus_seconds(FORAGE_COOLDOWN))
+            .filter(|ready_at| env.block.time < *ready_at)
+        {
+            return Err(ContractError::ForageCooldown {
+                ready_at: ready_at.seconds(),
+            });
+        }
+        player.last_forage = Some(env.block.time);
         player.berries += 1;
         PLAYERS.save(deps.storage, id.clone(), &player)?;
-        Ok(Response::default())
+        increment_score(deps.storage, LeaderboardKind::Berries, &id)?;
+        walk_eggs(deps.storage, &id)?;
+        let quests = advance_quests(deps.storage, &id, QuestAction::CollectBerries)?;
+        Ok(Response::new()
+            .add_event(
+                event("collect_berries")
+                    .add_attribute("player", id)
+                    .add_attribute("sender", info.sender)
+                    .add_attribute("berries_delta", "1"),
+            )
+            .add_events(quests))
+    }
+
+    pub fn breed(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        env: Env,
+        id: String,
+        first: i32,
+        second: i32,
+    ) -> ArchwayResult<ContractError> {
+        let player = load_owned_player(deps.storage, &id, &info.sender)?;
+        if first == second {
+            return Err(ContractError::IncompatibleParents { first, second });
+        }
+        let mut parents = vec![];
+        let mut types = vec![];
+        let mut genes = vec![];
+        for index in [first, second] {
+            let pokemon = &player.pokemons[roster_index(&player, index)?];
+            if pokemon.is_fainted() {
+                return Err(ContractError::PokemonFainted { index });
+            }
+            let key = (pokemon.collection.clone(), pokemon.token_id);
+            if let Some(ready_at) = BREEDING_COOLDOWNS.may_load(deps.storage, key)? {
+                if env.block.time < ready_at {
+                    return Err(ContractError::BreedingCooldown {
+                        index,
+                        ready_at: ready_at.seconds(),
+                    });
+                }
+            }
+            types.push(SPECIES.load(deps.storage, pokemon.species.clone())?.types);
+            genes.push(pokemon.genes.clone());
+            parents.push(Parent {
+                collection: pokemon.collection.clone(),
+                token_id: pokemon.token_id,
+                species: pokemon.species.clone(),
+            });
+        }
+        if !types[0].iter().any(|kind| types[1].contains(kind)) {
+            return Err(ContractError::IncompatibleParents { first, second });
+        }
+        // the supply is only taken on hatching, but eggs that can't hatch aren't laid
+        let species = SPECIES.load(deps.storage, parents[0].species.clone())?;
+        ensure_supply(deps.storage, &species, &id)?;
+
+        let config = BREEDING.load(deps.storage)?;
+        let ready_at = env.block.time.plus_seconds(config.cooldown);
+        for parent in &parents {
+            let key = (parent.collection.clone(), parent.token_id);
+            BREEDING_COOLDOWNS.save(deps.storage, key, &ready_at)?;
+        }
+        let egg_id = EGG_COUNT.load(deps.storage)? + 1;
+        EGG_COUNT.save(deps.storage, &egg_id)?;
+        let mut rng = Rng::new(&env, &[id.as_bytes(), &egg_id.to_be_bytes()]);
+        let egg = Egg {
+            species: parents[0].species.clone(),
+            parents,
+            laid_at: env.block.height,
+            steps: 0,
+            genes: inherit_genes(&mut rng, &genes[0], &genes[1]),
+        };
+        EGGS.save(deps.storage, (id.clone(), egg_id), &egg)?;
+        Ok(Response::new().add_event(
+            event("breed")
+                .add_attribute("player", id)
+                .add_attribute("sender", info.sender)
+                .add_attribute("egg", egg_id.to_string())
+                .add_attribute("species", egg.species)
+                .add_attribute("first", first.to_string())
+                .add_attribute("second", second.to_string()),
+        ))
+    }
+
+    pub fn hatch_egg(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        env: Env,
+        id: String,
+        egg_id: u64,
+    ) -> ArchwayResult<ContractError> {
+        let player = load_owned_player(deps.storage, &id, &info.sender)?;
+        let key = (id.clone(), egg_id);
+        let Some(egg) = EGGS.may_load(deps.storage, key.clone())? else {
+            return Err(ContractError::UnknownEgg { id: egg_id });
+        };
+        if !egg.is_ready(&BREEDING.load(deps.storage)?, env.block.height) {
+            return Err(ContractError::EggNotReady { id: egg_id });
+        }
+        EGGS.remove(deps.storage, key);
+
+        let species = SPECIES.load(deps.storage, egg.species.clone())?;
+        take_supply(deps.storage, &species, &id)?;
+        let nft_address = species_collection(deps.storage, &species)?;
+        let token = next_token_id(deps.as_ref(), &nft_address)?;
+        LINEAGE.save(deps.storage, (nft_address.clone(), token), &egg.parents)?;
+        let pokemon = Pokemon {
+            collection: nft_address,
+            token_id: token,
+            species: egg.species.clone(),
+            index: player.pokemons.len() as i32,
+            health: MAX_HEALTH,
+            level: 1,
+            xp: 0,
+            original_trainer: id.clone(),
+            variant: None,
+            genes: egg.genes.clone(),
+            moves: starting_moves(deps.storage, &species, 1)?,
+        };
+        let mint = mint_msg(deps.storage, &info.sender, &id, pokemon, false)?;
+        Ok(Response::new().add_submessage(mint).add_event(
+            event("hatch_egg")
+                .add_attribute("player", id)
+                .add_attribute("sender", info.sender)
+                .add_attribute("egg", egg_id.to_string())
+                .add_attribute("token_id", token.to_string())
+                .add_attribute("species", egg.species),
+        ))
+    }
+
+    pub fn set_breeding_config(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        config: BreedingConfig,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        BREEDING.save(deps.storage, &config)?;
+        Ok(Response::new().add_event(
+            event("set_breeding_config")
+                .add_attribute("sender", info.sender)
+                .add_attribute("cooldown", config.cooldown.to_string())
+                .add_attribute("hatch_blocks", config.hatch_blocks.to_string())
+                .add_attribute("hatch_steps", config.hatch_steps.to_string()),
+        ))
+    }
+
+    /// Counts a step towards hatching every egg of `id`.
+    fn walk_eggs(storage: &mut dyn Storage, id: &str) -> StdResult<()> {
+        let eggs = EGGS
+            .prefix(id.to_string())
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for (egg_id, mut egg) in eggs {
+            egg.steps += 1;
+            EGGS.save(storage, (id.to_string(), egg_id), &egg)?;
+        }
+        Ok(())
     }
 
     pub fn set_default_pokemon(
-        deps: DepsMut,
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
         id: String,
         pokemon: i32,
-    ) -> Result<Response, ContractError> {
-        let mut player = PLAYERS.load(deps.storage, id.clone())?;
+    ) -> ArchwayResult<ContractError> {
+        let mut player = load_owned_player(deps.storage, &id, &info.sender)?;
+        roster_index(&player, pokemon)?;
         player.default_pokemon = pokemon;
         PLAYERS.save(deps.storage, id.clone(), &player)?;
-        Ok(Response::default())
+        Ok(Response::new().add_event(
+            event("set_default_pokemon")
+                .add_attribute("player", id)
+                .add_attribute("sender", info.sender)
+                .add_attribute("pokemon", pokemon.to_string()),
+        ))
     }
 
     pub fn catch_pokemon(
-        deps: DepsMut,
+        deps: DepsMut<ArchwayQuery>,
         info: MessageInfo,
-        env: Env,
         id: String,
-        token_uri: String,
+        species: String,
         health: i32,
         curr_pokemon: i32,
-    ) -> Result<Response, ContractError> {
-        let token = TOKEN.load(deps.storage)?;
-        let nft_address = NFT_CONTRACT.load(deps.storage)?;
+    ) -> ArchwayResult<ContractError> {
+        let Some(data) = SPECIES.may_load(deps.storage, species.clone())? else {
+            return Err(ContractError::UnknownSpecies { species });
+        };
+        let nft_address = species_collection(deps.storage, &data)?;
+        let token = next_token_id(deps.as_ref(), &nft_address)?;
 
-        let mint: Cw721ExecuteMsg = Cw721ExecuteMsg::Mint {
-            token_id: (token + 1).to_string(),
-            owner: info.sender.clone().to_string(),
-            token_uri: Some(token_uri),
-            extension: TokenExtension {
-                publisher: "PokeArch".to_string(),
-            },
+        let mut player = load_owned_player(deps.storage, &id, &info.sender)?;
+        ensure_badge(&player, &data.required_badge)?;
+        let curr = roster_index(&player, curr_pokemon)?;
+        if player.pokemons[curr].is_fainted() {
+            return Err(ContractError::PokemonFainted {
+                index: curr_pokemon,
+            });
+        }
+        let Some(encounter) = player
+            .encounter
+            .take()
+            .filter(|encounter| encounter.species == species)
+        else {
+            return Err(ContractError::NotEncountered { species });
         };
+        take_supply(deps.storage, &data, &id)?;
+        let variant = encounter.variant;
+        if let Some(variant) = variant {
+            let key = (variant.as_str().to_string(), species.clone());
+            VARIANTS_CAUGHT.update(deps.storage, key, |count| -> StdResult<_> {
+                Ok(count.unwrap_or_default() + 1)
+            })?;
+        }
+        let health = health.clamp(0, MAX_HEALTH);
+        let health_delta = health - player.pokemons[curr].health;
+        player.pokemons[curr].health = health;
+        PLAYERS.save(deps.storage, id.clone(), &player)?;
 
-        let wasm_msg = WasmMsg::Execute {
-            contract_addr: nft_address.clone().to_string(),
-            msg: to_json_binary(&mint)?,
-            funds: Vec::new(),
+        let pokemon = Pokemon {
+            collection: nft_address.clone(),
+            token_id: token,
+            species: species.clone(),
+            index: player.pokemons.len() as i32,
+            health: MAX_HEALTH,
+            level: encounter.level,
+            xp: (encounter.level - 1) * XP_PER_LEVEL,
+            original_trainer: id.clone(),
+            variant,
+            genes: encounter.genes,
+            moves: starting_moves(deps.storage, &data, encounter.level)?,
         };
+        let mint = mint_msg(deps.storage, &info.sender, &id, pokemon, true)?;
+        let mut event = event("catch")
+            .add_attribute("player", id)
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token.to_string())
+            .add_attribute("species", species)
+            .add_attribute("level", encounter.level.to_string())
+            .add_attribute("pokemon", curr_pokemon.to_string())
+            .add_attribute("health_delta", health_delta.to_string());
+        if let Some(variant) = variant {
+            event = event.add_attribute("variant", variant.as_str());
+        }
+        Ok(Response::new().add_submessage(mint).add_event(event))
+    }
 
-        let mut player = PLAYERS.load(deps.storage, id.clone())?;
-        player.pokemons.push(Pokemon {
-            token_id: token + 1,
-            index: (player.pokemons.len() as i32),
-            health: 100,
-        });
-        player.pokemons[curr_pokemon as usize].health = health;
+    pub fn explore(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        env: Env,
+        id: String,
+        region_id: String,
+    ) -> ArchwayResult<ContractError> {
+        let mut player = load_owned_player(deps.storage, &id, &info.sender)?;
+        let Some(region) = REGIONS.may_load(deps.storage, region_id.clone())? else {
+            return Err(ContractError::UnknownRegion { id: region_id });
+        };
+        ensure_badge(&player, &region.required_badge)?;
 
-        PLAYERS.save(deps.storage, id.clone(), &player)?;
-        TOKEN.save(deps.storage, &(token + 1))?;
-        Ok(Response::new().add_message(wasm_msg))
-    }
-}
+        // running from a pokemon takes time, or a ticket to look for another one right away
+        let on_cooldown = player
+            .encounter
+            .as_ref()
+            .map(|encounter| encounter.found_at.plus_seconds(EXPLORE_COOLDOWN))
+            .filter(|ready_at| env.block.time < *ready_at);
+        let mut tickets_delta = 0;
+        if let Some(ready_at) = on_cooldown {
+            if player.tickets < 1 {
+                return Err(ContractError::ExploreCooldown {
+                    ready_at: ready_at.seconds(),
+                });
+            }
+            player.tickets -= 1;
+            tickets_delta = -1;
+        }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::CheckAllowance { addr } => to_json_binary(&query::check_allowance(deps, addr)?),
-        QueryMsg::GetPlayer { id } => to_json_binary(&query::get_player(deps, id)?),
+        let hour = (env.block.time.seconds() % SECONDS_PER_DAY / 3600) as u32;
+        let mut spawns = vec![];
+        for spawn in region.spawns {
+            if !spawn.window.is_none_or(|window| window.contains(hour)) {
+                continue;
+            }
+            // species the player couldn't catch don't show up, nor do limited ones that ran
+            // out or whose window closed
+            let species = SPECIES.load(deps.storage, spawn.species.clone())?;
+            if ensure_badge(&player, &species.required_badge).is_err() {
+                continue;
+            }
+            let spawning = species
+                .supply
+                .as_ref()
+                .is_none_or(|supply| supply.is_spawning(env.block.time));
+            if !spawning {
+                continue;
+            }
+            match ensure_supply(deps.storage, &species, &id) {
+                Ok(()) => spawns.push(spawn),
+                Err(ContractError::SoldOut { .. } | ContractError::CatchLimit { .. }) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        let mut rng = Rng::new(&env, &[id.as_bytes(), region_id.as_bytes()]);
+        let Some(spawn) = rng.weighted(&spawns, |spawn| spawn.weight.into()) else {
+            return Err(ContractError::NothingSpawns { region: region_id });
+        };
+        let levels = (spawn.max_level - spawn.min_level + 1) as u64;
+        let level = spawn.min_level + rng.below(levels) as i32;
+        let odds = match SPECIES
+            .load(deps.storage, spawn.species.clone())?
+            .variant_odds
+        {
+            Some(odds) => odds,
+            None => VARIANT_ODDS.load(deps.storage)?,
+        };
+        let encounter = Encounter {
+            region: region_id,
+            species: spawn.species.clone(),
+            level,
+            variant: roll_variant(&mut rng, odds),
+            genes: roll_genes(&mut rng),
+            found_at: env.block.time,
+        };
+        let mut event = event("explore")
+            .add_attribute("player", id.clone())
+            .add_attribute("sender", info.sender)
+            .add_attribute("region", encounter.region.clone())
+            .add_attribute("species", encounter.species.clone())
+            .add_attribute("level", encounter.level.to_string())
+            .add_attribute("tickets_delta", tickets_delta.to_string());
+        if let Some(variant) = encounter.variant {
+            event = event.add_attribute("variant", variant.as_str());
+        }
+        player.encounter = Some(encounter);
+        PLAYERS.save(deps.storage, id, &player)?;
+        Ok(Response::new().add_event(event))
     }
-}
 
-pub mod query {
+    pub fn add_allowance(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        addr: String,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        let addr = deps.api.addr_validate(&addr)?;
+        ALLOWED_ADDRESSES.save(deps.storage, addr.clone(), &Empty::default())?;
+        Ok(Response::new().add_event(
+            event("add_allowance")
+                .add_attribute("sender", info.sender)
+                .add_attribute("address", addr),
+        ))
+    }
 
-    use crate::msg::PlayerResponse;
+    pub fn remove_allowance(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        addr: String,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        let addr = deps.api.addr_validate(&addr)?;
+        ALLOWED_ADDRESSES.remove(deps.storage, addr.clone());
+        Ok(Response::new().add_event(
+            event("remove_allowance")
+                .add_attribute("sender", info.sender)
+                .add_attribute("address", addr),
+        ))
+    }
 
-    use super::*;
+    pub fn set_move(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        id: String,
+        details: Move,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        if details.pp == 0 || !(1..=100).contains(&details.accuracy) {
+            return Err(ContractError::InvalidMove { id });
+        }
+        MOVES.save(deps.storage, id.clone(), &details)?;
+        Ok(Response::new().add_event(
+            event("set_move")
+                .add_attribute("sender", info.sender)
+                .add_attribute("move", id),
+        ))
+    }
 
-    pub fn check_allowance(deps: Deps, addr: String) -> StdResult<bool> {
-        Ok(ALLOWED_ADDRESSES.has(deps.storage, deps.api.addr_validate(&addr)?))
+    pub fn learn_move(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        id: String,
+        index: i32,
+        move_id: String,
+    ) -> ArchwayResult<ContractError> {
+        let mut player = load_owned_player(deps.storage, &id, &info.sender)?;
+        let roster = roster_index(&player, index)?;
+        let pokemon = &mut player.pokemons[roster];
+        let species = SPECIES.load(deps.storage, pokemon.species.clone())?;
+        let learnable = species
+            .learnset
+            .iter()
+            .any(|learnable| learnable.id == move_id && learnable.level <= pokemon.level);
+        if !learnable {
+            return Err(ContractError::CannotLearnMove {
+                species: species.name,
+                id: move_id,
+            });
+        }
+        if pokemon.moves.iter().any(|known| known.id == move_id) {
+            return Err(ContractError::MoveAlreadyKnown { id: move_id });
+        }
+        if pokemon.moves.len() >= MAX_MOVES {
+            return Err(ContractError::TooManyMoves { max: MAX_MOVES });
+        }
+        let pp = MOVES.load(deps.storage, move_id.clone())?.pp;
+        pokemon.moves.push(KnownMove {
+            id: move_id.clone(),
+            pp,
+        });
+        PLAYERS.save(deps.storage, id.clone(), &player)?;
+        Ok(Response::new().add_event(
+            event("learn_move")
+                .add_attribute("player", id)
+                .add_attribute("sender", info.sender)
+                .add_attribute("pokemon", index.to_string())
+                .add_attribute("move", move_id),
+        ))
     }
-    pub fn get_player(deps: Deps, id: String) -> StdResult<PlayerResponse> {
-        let player = PLAYERS.load(deps.storage, id)?;
-        Ok(PlayerResponse { player })
+
+    pub fn forget_move(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        id: String,
+        index: i32,
+        move_id: String,
+    ) -> ArchwayResult<ContractError> {
+        let mut player = load_owned_player(deps.storage, &id, &info.sender)?;
+        let roster = roster_index(&player, index)?;
+        let pokemon = &mut player.pokemons[roster];
+        let Some(known) = pokemon.moves.iter().position(|known| known.id == move_id) else {
+            return Err(ContractError::MoveNotKnown { id: move_id });
+        };
+        pokemon.moves.remove(known);
+        PLAYERS.save(deps.storage, id.clone(), &player)?;
+        Ok(Response::new().add_event(
+            event("forget_move")
+                .add_attribute("player", id)
+                .add_attribute("sender", info.sender)
+                .add_attribute("pokemon", index.to_string())
+                .add_attribute("move", move_id),
+        ))
     }
-}
 
-#[entry_point]
-pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
-    return match msg {
-        SudoMsg::CwGrant(grant) => process_grant(deps, grant),
-        _ => Err(ContractError::Unauthorized {}),
-    };
-}
+    fn battle_moves(storage: &dyn Storage, moves: &[KnownMove]) -> StdResult<Vec<BattleMove>> {
+        moves
+            .iter()
+            .map(|known| {
+                Ok(BattleMove {
+                    details: MOVES.load(storage, known.id.clone())?,
+                    pp: known.pp,
+                })
+            })
+            .collect()
+    }
 
-fn process_grant(deps: DepsMut, grant: CwGrant) -> Result<Response, ContractError> {
-    const TYPE_URL: &str = "cosmwasm.wasm.v1.MsgExecuteContract";
+    /// The last moves of the learnset a new pokemon of `level` can know, with full PP.
+    fn starting_moves(
+        storage: &dyn Storage,
+        species: &Species,
+        level: i32,
+    ) -> StdResult<Vec<KnownMove>> {
+        let mut learnset: Vec<_> = species
+            .learnset
+            .iter()
+            .filter(|learnable| learnable.level <= level)
+            .collect();
+        learnset.sort_by_key(|learnable| learnable.level);
+        let skip = learnset.len().saturating_sub(MAX_MOVES);
+        learnset
+            .into_iter()
+            .skip(skip)
+            .map(|learnable| {
+                let pp = MOVES.load(storage, learnable.id.clone())?.pp;
+                Ok(KnownMove {
+                    id: learnable.id.clone(),
+                    pp,
+                })
+            })
+            .collect()
+    }
 
-    for msg in grant.msgs {
-        // we check if all the senders are in the allow list
-        let addr = deps.api.addr_validate(&msg.sender)?;
-        if !ALLOWED_ADDRESSES.has(deps.storage, addr) {
+    pub fn set_region(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        id: String,
+        region: Region,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
             return Err(ContractError::Unauthorized {});
         }
-
-        // we check the message type url
-        if msg.type_url != TYPE_URL {
-            return Err(ContractError::DisallowedMessage(msg.type_url));
+        for spawn in &region.spawns {
+            if !SPECIES.has(deps.storage, spawn.species.clone()) {
+                return Err(ContractError::UnknownSpecies {
+                    species: spawn.species.clone(),
+                });
+            }
+            let hours = spawn
+                .window
+                .is_none_or(|window| window.start < 24 && window.end < 24);
+            if spawn.weight == 0
+                || spawn.min_level < 1
+                || spawn.min_level > spawn.max_level
+                || !hours
+            {
+                return Err(ContractError::InvalidSpawn {
+                    species: spawn.species.clone(),
+                });
+            }
         }
+        REGIONS.save(deps.storage, id.clone(), &region)?;
+        Ok(Response::new().add_event(
+            event("set_region")
+                .add_attribute("sender", info.sender)
+                .add_attribute("region", id)
+                .add_attribute("spawns", region.spawns.len().to_string()),
+        ))
     }
 
-    Ok(Response::default())
-}
-
-#[cfg(test)]
-mod tests {
-    use std::alloc::System;
+    pub fn record_battle(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        winner: String,
+        loser: String,
+        pokemon: i32,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        if winner == loser || !PLAYERS.has(deps.storage, loser.clone()) {
+            return Err(ContractError::InvalidBattle {});
+        }
 
-    use crate::msg::PlayerResponse;
-    use crate::state::{Player, Pokemon};
+        let mut player = PLAYERS.load(deps.storage, winner.clone())?;
+        let index = roster_index(&player, pokemon)?;
+        let winning = &mut player.pokemons[index];
+        if winning.is_fainted() {
+            return Err(ContractError::PokemonFainted { index: pokemon });
+        }
+        let level_up = gain_xp(deps.storage, &winner, winning, XP_PER_WIN)?;
+        let level = winning.level;
+        let token_id = winning.token_id;
+        player.wins += 1;
+        PLAYERS.save(deps.storage, winner.clone(), &player)?;
 
-    use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_json, Addr};
+        let season = SEASON.load(deps.storage)?;
+        LeaderboardKind::Wins
+            .board()
+            .set_score(deps.storage, season, &winner, player.wins)?;
+        add_guild_score(deps.storage, LeaderboardKind::Wins, &winner, 1)?;
+        let quests = advance_quests(deps.storage, &winner, QuestAction::WinBattle)?;
+        Ok(Response::new()
+            .add_event(
+                event("record_battle")
+                    .add_attribute("player", winner)
+                    .add_attribute("loser", loser)
+                    .add_attribute("pokemon", pokemon.to_string())
+                    .add_attribute("token_id", token_id.to_string())
+                    .add_attribute("xp_delta", XP_PER_WIN.to_string())
+                    .add_attribute("level", level.to_string()),
+            )
+            .add_events(level_up)
+            .add_events(quests))
+    }
 
-    #[test]
-    fn proper_initialization() {
-        let mut deps = mock_dependencies();
+    pub fn revive(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        id: String,
+        pokemon: i32,
+    ) -> ArchwayResult<ContractError> {
+        let mut player = load_owned_player(deps.storage, &id, &info.sender)?;
+        let index = roster_index(&player, pokemon)?;
+        if !player.pokemons[index].is_fainted() {
+            return Err(ContractError::PokemonNotFainted { index: pokemon });
+        }
+        if player.revives <= 0 {
+            return Err(ContractError::NoRevives {});
+        }
+        player.revives -= 1;
+        let revived = &mut player.pokemons[index];
+        let health_delta = MAX_HEALTH / 2 - revived.health;
+        revived.health = MAX_HEALTH / 2;
+        let token_id = revived.token_id;
+        PLAYERS.save(deps.storage, id.clone(), &player)?;
+        Ok(Response::new().add_event(
+            event("revive")
+                .add_attribute("player", id)
+                .add_attribute("sender", info.sender)
+                .add_attribute("pokemon", pokemon.to_string())
+                .add_attribute("token_id", token_id.to_string())
+                .add_attribute("health_delta", health_delta.to_string())
+                .add_attribute("revives_delta", "-1"),
+        ))
+    }
 
-        let msg = InstantiateMsg {};
-        let info = mock_info("creator", &coins(1000, "earth"));
+    /// Heals the whole party, fainted pokemon included. A visit is free once the cooldown since
+    /// the previous one has passed; before that it costs the configured fee, if there is one.
+    pub fn visit_pokemon_center(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        env: Env,
+        id: String,
+    ) -> ArchwayResult<ContractError> {
+        let center = POKEMON_CENTER.load(deps.storage)?;
+        let mut player = load_owned_player(deps.storage, &id, &info.sender)?;
 
-        // we can just call .unwrap() to assert this was a success
-        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(1, res.messages.len());
+        let on_cooldown = player
+            .last_center_visit
+            .map(|visit| visit.plus_seconds(center.cooldown))
+            .filter(|ready_at| env.block.time < *ready_at);
+        let mut fee_paid = String::from("none");
+        if let Some(ready_at) = on_cooldown {
+            // without funds the player is only told to wait
+            let fee = center.fee.filter(|_| !info.funds.is_empty()).ok_or(
+                ContractError::PokemonCenterCooldown {
+                    ready_at: ready_at.seconds(),
+                },
+            )?;
+            if info.funds != [fee.clone()] {
+                return Err(ContractError::WrongFee {
+                    fee: fee.to_string(),
+                });
+            }
+            fee_paid = fee.to_string();
+        } else if !info.funds.is_empty() {
+            return Err(ContractError::UnexpectedFunds {});
+        }
 
-        let res = query(
-            deps.as_ref(),
-            mock_env(),
-            QueryMsg::CheckAllowance {
-                addr: Addr::unchecked("creator").to_string(),
-            },
-        )
-        .unwrap();
-        let value: bool = from_json(&res).unwrap();
-        assert_eq!(true, value);
+        let mut health_delta = 0;
+        for pokemon in player.pokemons.iter_mut() {
+            health_delta += MAX_HEALTH - pokemon.health;
+            pokemon.health = MAX_HEALTH;
+            for known in pokemon.moves.iter_mut() {
+                known.pp = MOVES.load(deps.storage, known.id.clone())?.pp;
+            }
+        }
+        player.last_center_visit = Some(env.block.time);
+        PLAYERS.save(deps.storage, id.clone(), &player)?;
+        Ok(Response::new().add_event(
+            event("visit_pokemon_center")
+                .add_attribute("player", id)
+                .add_attribute("sender", info.sender)
+                .add_attribute("health_delta", health_delta.to_string())
+                .add_attribute("fee", fee_paid),
+        ))
+    }
+
+    pub fn set_pokemon_center(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        cooldown: u64,
+        fee: Option<Coin>,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        let fee_attribute = fee
+            .as_ref()
+            .map(|fee| fee.to_string())
+            .unwrap_or_else(|| String::from("none"));
+        POKEMON_CENTER.save(deps.storage, &PokemonCenter { cooldown, fee })?;
+        Ok(Response::new().add_event(
+            event("set_pokemon_center")
+                .add_attribute("sender", info.sender)
+                .add_attribute("cooldown", cooldown.to_string())
+                .add_attribute("fee", fee_attribute),
+        ))
+    }
+
+    pub fn set_species(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        species: Species,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        if let Some(id) = &species.collection {
+            if !COLLECTIONS.has(deps.storage, id.clone()) {
+                return Err(ContractError::UnknownCollection { id: id.clone() });
+            }
+        }
+        for learnable in &species.learnset {
+            if !MOVES.has(deps.storage, learnable.id.clone()) {
+                return Err(ContractError::UnknownMove {
+                    id: learnable.id.clone(),
+                });
+            }
+        }
+        SPECIES.save(deps.storage, species.name.clone(), &species)?;
+        Ok(Response::new().add_event(
+            event("set_species")
+                .add_attribute("sender", info.sender)
+                .add_attribute("species", species.name),
+        ))
+    }
+
+    pub fn set_starters(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        starters: Vec<String>,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        for species in &starters {
+            if !SPECIES.has(deps.storage, species.clone()) {
+                return Err(ContractError::UnknownSpecies {
+                    species: species.clone(),
+                });
+            }
+        }
+        STARTERS.save(deps.storage, &starters)?;
+        Ok(Response::new().add_event(
+            event("set_starters")
+                .add_attribute("sender", info.sender)
+                .add_attribute("starters", starters.join(",")),
+        ))
+    }
+
+    pub fn set_metadata_renderer(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        base_uri: String,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        let base_uri = base_uri.trim_end_matches('/');
+        METADATA_RENDERER.save(deps.storage, &base_uri.to_string())?;
+        Ok(Response::new().add_event(
+            event("set_metadata_renderer")
+                .add_attribute("sender", info.sender)
+                .add_attribute("base_uri", base_uri),
+        ))
+    }
+
+    pub fn set_archid(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        registry: String,
+        token: Option<String>,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        let archid = ArchId {
+            registry: deps.api.addr_validate(&registry)?,
+            token: token
+                .map(|token| deps.api.addr_validate(&token))
+                .transpose()?,
+        };
+        ARCHID.save(deps.storage, &archid)?;
+        Ok(Response::new().add_event(
+            event("set_archid")
+                .add_attribute("sender", info.sender)
+                .add_attribute("registry", registry),
+        ))
+    }
+
+    pub fn reverify_player(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        env: Env,
+        id: String,
+    ) -> ArchwayResult<ContractError> {
+        let archid = ARCHID
+            .may_load(deps.storage)?
+            .ok_or(ContractError::ArchIdNotConfigured {})?;
+        verify_name(deps.as_ref(), &env, &archid, &id, &info.sender)?;
+
+        let mut player = PLAYERS.load(deps.storage, id.clone())?;
+        let previous = player.address;
+        player.address = info.sender.clone();
+        PLAYERS.save(deps.storage, id.clone(), &player)?;
+        Ok(Response::new().add_event(
+            event("reverify_player")
+                .add_attribute("player", id)
+                .add_attribute("sender", info.sender)
+                .add_attribute("previous", previous),
+        ))
+    }
+
+    pub fn set_rewards_metadata(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        owner_address: Option<String>,
+        rewards_address: Option<String>,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        let owner_address = owner_address
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?;
+        let rewards_address = rewards_address
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?;
+
+        let mut event = event("set_rewards_metadata").add_attribute("sender", info.sender);
+        if let Some(addr) = &owner_address {
+            event = event.add_attribute("owner_address", addr);
+        }
+        if let Some(addr) = &rewards_address {
+            event = event.add_attribute("rewards_address", addr);
+        }
+        let msg = ArchwayMsg::UpdateContractMetadata {
+            contract_address: None,
+            owner_address: owner_address.map(String::from),
+            rewards_address: rewards_address.map(String::from),
+        };
+        Ok(Response::new().add_message(msg).add_event(event))
+    }
+
+    /// Withdraws all pending rewards. They are split once the reply reports the amounts.
+    pub fn withdraw_rewards(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        let msg = SubMsg::reply_on_success(
+            ArchwayMsg::withdraw_max_rewards(),
+            WITHDRAW_REWARDS_REPLY_ID,
+        );
+        Ok(Response::new()
+            .add_submessage(msg)
+            .add_event(event("withdraw_rewards").add_attribute("sender", info.sender)))
+    }
+
+    pub fn set_rewards_split(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        gas_tank: Decimal,
+        prize_pool: Decimal,
+        treasury: String,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        // shares that overflow together are well over one as well
+        if !gas_tank
+            .checked_add(prize_pool)
+            .is_ok_and(|sum| sum <= Decimal::one())
+        {
+            return Err(ContractError::InvalidRewardsSplit {});
+        }
+        let treasury = deps.api.addr_validate(&treasury)?;
+        REWARDS_SPLIT.save(
+            deps.storage,
+            &RewardsSplit {
+                gas_tank,
+                prize_pool,
+                treasury: treasury.clone(),
+            },
+        )?;
+        Ok(Response::new().add_event(
+            event("set_rewards_split")
+                .add_attribute("sender", info.sender)
+                .add_attribute("gas_tank", gas_tank.to_string())
+                .add_attribute("prize_pool", prize_pool.to_string())
+                .add_attribute("treasury", treasury),
+        ))
+    }
+
+    pub fn fund_gas_tank(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+    ) -> ArchwayResult<ContractError> {
+        let mut gas_tank = GAS_TANK.load(deps.storage)?;
+        for coin in info.funds.iter().cloned() {
+            add_coin(&mut gas_tank, coin)?;
+        }
+        GAS_TANK.save(deps.storage, &gas_tank)?;
+        Ok(Response::new().add_event(
+            event("fund_gas_tank")
+                .add_attribute("sender", info.sender)
+                .add_attribute("amount", coins_to_string(&info.funds)),
+        ))
+    }
+
+    pub fn award_prize(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        id: String,
+        amount: Coin,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        let player = PLAYERS.load(deps.storage, id.clone())?;
+        let mut prize_pool = PRIZE_POOL.load(deps.storage)?;
+        sub_coin(&mut prize_pool, amount.clone()).map_err(|_| ContractError::PrizePoolEmpty {
+            amount: amount.to_string(),
+        })?;
+        PRIZE_POOL.save(deps.storage, &prize_pool)?;
+
+        let msg = BankMsg::Send {
+            to_address: player.address.to_string(),
+            amount: vec![amount.clone()],
+        };
+        Ok(Response::new().add_message(msg).add_event(
+            event("award_prize")
+                .add_attribute("player", id)
+                .add_attribute("sender", info.sender)
+                .add_attribute("amount", amount.to_string()),
+        ))
+    }
+
+    pub fn set_premium(
+        deps: DepsMut<ArchwayQuery>,
+        env: Env,
+        info: MessageInfo,
+        flat_fee: Coin,
+        grant_policy: GrantPolicy,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        PREMIUM.save(
+            deps.storage,
+            &Premium {
+                flat_fee: (!flat_fee.amount.is_zero()).then(|| flat_fee.clone()),
+                grant_policy,
+            },
+        )?;
+        let event = event("set_premium")
+            .add_attribute("sender", info.sender)
+            .add_attribute("flat_fee", flat_fee.to_string())
+            .add_attribute("grant_policy", grant_policy.as_str());
+        let msg = ArchwayMsg::set_flat_fee(env.contract.address, flat_fee);
+        Ok(Response::new().add_message(msg).add_event(event))
+    }
+
+    pub fn set_paused(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        paused: bool,
+    ) -> ArchwayResult<ContractError> {
+        ensure_guardian(deps.as_ref(), &info.sender)?;
+        PAUSED.save(deps.storage, &paused)?;
+        let action = if paused { "pause" } else { "unpause" };
+        Ok(Response::new().add_event(event(action).add_attribute("sender", info.sender)))
+    }
+
+    pub fn set_feature(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        feature: Feature,
+        enabled: bool,
+    ) -> ArchwayResult<ContractError> {
+        ensure_guardian(deps.as_ref(), &info.sender)?;
+        DISABLED_FEATURES.update(deps.storage, |mut disabled| -> StdResult<_> {
+            disabled.retain(|f| *f != feature);
+            if !enabled {
+                disabled.push(feature);
+            }
+            Ok(disabled)
+        })?;
+        Ok(Response::new().add_event(
+            event("set_feature")
+                .add_attribute("sender", info.sender)
+                .add_attribute("feature", feature.as_str())
+                .add_attribute("enabled", enabled.to_string()),
+        ))
+    }
+
+    pub fn add_guardian(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        addr: String,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        let addr = deps.api.addr_validate(&addr)?;
+        GUARDIANS.save(deps.storage, addr.clone(), &Empty {})?;
+        Ok(Response::new().add_event(
+            event("add_guardian")
+                .add_attribute("sender", info.sender)
+                .add_attribute("address", addr),
+        ))
+    }
+
+    pub fn remove_guardian(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        addr: String,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        let addr = deps.api.addr_validate(&addr)?;
+        GUARDIANS.remove(deps.storage, addr.clone());
+        Ok(Response::new().add_event(
+            event("remove_guardian")
+                .add_attribute("sender", info.sender)
+                .add_attribute("address", addr),
+        ))
+    }
+
+    /// Fails while the game is paused or `feature` is switched off.
+    pub fn ensure_enabled(
+        storage: &dyn Storage,
+        feature: Option<Feature>,
+    ) -> Result<(), ContractError> {
+        if PAUSED.load(storage)? {
+            return Err(ContractError::Paused {
+                feature: String::from("all"),
+            });
+        }
+        if let Some(feature) = feature {
+            if DISABLED_FEATURES.load(storage)?.contains(&feature) {
+                return Err(ContractError::Paused {
+                    feature: feature.as_str().to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn ensure_guardian(deps: Deps<ArchwayQuery>, sender: &Addr) -> Result<(), ContractError> {
+        if *sender != OWNER.load(deps.storage)? && !GUARDIANS.has(deps.storage, sender.clone()) {
+            return Err(ContractError::Unauthorized {});
+        }
+        Ok(())
+    }
+
+    pub fn reset_season(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        // standings of the closed season stay in storage under their season key
+        let season = SEASON.update(deps.storage, |season| -> StdResult<_> { Ok(season + 1) })?;
+        Ok(Response::new().add_event(
+            event("reset_season")
+                .add_attribute("sender", info.sender)
+                .add_attribute("season", season.to_string()),
+        ))
+    }
+
+    pub fn set_gym(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        id: String,
+        gym: Gym,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        if gym.team.is_empty() || gym.team.len() > MAX_PARTY {
+            return Err(ContractError::InvalidParty { max: MAX_PARTY });
+        }
+        for member in &gym.team {
+            if !SPECIES.has(deps.storage, member.species.clone()) {
+                return Err(ContractError::UnknownSpecies {
+                    species: member.species.clone(),
+                });
+            }
+        }
+        GYMS.save(deps.storage, id.clone(), &gym)?;
+        Ok(Response::new().add_event(
+            event("set_gym")
+                .add_attribute("sender", info.sender)
+                .add_attribute("gym", id)
+                .add_attribute("badge", gym.badge),
+        ))
+    }
+
+    /// Battles a gym leader. The leader's team starts fully healed, the party with the health
+    /// it has, and the damage it takes stays after the battle, won or lost.
+    pub fn challenge_gym(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        env: Env,
+        id: String,
+        gym_id: String,
+        party: Vec<i32>,
+    ) -> ArchwayResult<ContractError> {
+        let mut player = load_owned_player(deps.storage, &id, &info.sender)?;
+        let Some(gym) = GYMS.may_load(deps.storage, gym_id.clone())? else {
+            return Err(ContractError::UnknownGym { id: gym_id });
+        };
+        ensure_badge(&player, &gym.required_badge)?;
+        let mut distinct = party.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+        if party.is_empty() || party.len() > MAX_PARTY || distinct.len() != party.len() {
+            return Err(ContractError::InvalidParty { max: MAX_PARTY });
+        }
+
+        let mut challengers = vec![];
+        for &index in &party {
+            let pokemon = &player.pokemons[roster_index(&player, index)?];
+            if pokemon.is_fainted() {
+                return Err(ContractError::PokemonFainted { index });
+            }
+            if pokemon.level < gym.min_level {
+                return Err(ContractError::LevelTooLow {
+                    index,
+                    required: gym.min_level,
+                });
+            }
+            let species = SPECIES.load(deps.storage, pokemon.species.clone())?;
+            challengers.push(Fighter {
+                stats: pokemon.stats(&species.base_stats),
+                types: species.types,
+                health: pokemon.health,
+                max_health: MAX_HEALTH,
+                moves: battle_moves(deps.storage, &pokemon.moves)?,
+            });
+        }
+        let mut defenders = vec![];
+        for member in &gym.team {
+            let species = SPECIES.load(deps.storage, member.species.clone())?;
+            // leaders' pokemon know what a new pokemon of their level would
+            let moves = starting_moves(deps.storage, &species, member.level)?;
+            defenders.push(Fighter {
+                stats: species.base_stats.at_level(member.level),
+                types: species.types,
+                health: MAX_HEALTH,
+                max_health: MAX_HEALTH,
+                moves: battle_moves(deps.storage, &moves)?,
+            });
+        }
+        let mut rng = Rng::new(&env, &[id.as_bytes(), gym_id.as_bytes()]);
+        let won = battle(&mut challengers, &mut defenders, &mut rng);
+
+        let mut health_delta = 0;
+        for (&index, fighter) in party.iter().zip(&challengers) {
+            let pokemon = &mut player.pokemons[index as usize];
+            health_delta += fighter.health - pokemon.health;
+            pokemon.health = fighter.health;
+            for (known, used) in pokemon.moves.iter_mut().zip(&fighter.moves) {
+                known.pp = used.pp;
+            }
+        }
+        let mut event = event("challenge_gym")
+            .add_attribute("player", id.clone())
+            .add_attribute("sender", info.sender)
+            .add_attribute("gym", gym_id)
+            .add_attribute("leader", gym.leader)
+            .add_attribute("result", if won { "won" } else { "lost" })
+            .add_attribute("health_delta", health_delta.to_string());
+        if won && !player.badges.contains(&gym.badge) {
+            event = event.add_attribute("badge", gym.badge.clone());
+            player.badges.push(gym.badge);
+        }
+        PLAYERS.save(deps.storage, id, &player)?;
+        Ok(Response::new().add_event(event))
+    }
+
+    pub fn set_quest(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        id: String,
+        quest: Quest,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        let reward = &quest.reward;
+        if quest.objective.count() == 0
+            || [reward.potions, reward.berries, reward.revives, reward.xp]
+                .iter()
+                .any(|amount| *amount < 0)
+        {
+            return Err(ContractError::InvalidAmount {});
+        }
+        if quest.guild && (reward.xp != 0 || reward.pokemon.is_some()) {
+            return Err(ContractError::InvalidGuildReward {});
+        }
+        if let Some(species) = &quest.reward.pokemon {
+            if !SPECIES.has(deps.storage, species.clone()) {
+                return Err(ContractError::UnknownSpecies {
+                    species: species.clone(),
+                });
+            }
+        }
+        QUESTS.save(deps.storage, id.clone(), &quest)?;
+        Ok(Response::new().add_event(
+            event("set_quest")
+                .add_attribute("sender", info.sender)
+                .add_attribute("quest", id)
+                .add_attribute("active", quest.active.to_string()),
+        ))
+    }
+
+    pub fn claim_quest(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        env: Env,
+        id: String,
+        quest_id: String,
+    ) -> ArchwayResult<ContractError> {
+        let mut player = load_owned_player(deps.storage, &id, &info.sender)?;
+        let Some(quest) = QUESTS.may_load(deps.storage, quest_id.clone())? else {
+            return Err(ContractError::UnknownQuest { id: quest_id });
+        };
+        if quest.guild {
+            return claim_guild_quest(deps, info, id, quest_id, quest);
+        }
+        let key = (id.clone(), quest_id.clone());
+        let mut progress = QUEST_PROGRESS
+            .may_load(deps.storage, key.clone())?
+            .unwrap_or_default();
+        if progress.claimed {
+            return Err(ContractError::QuestClaimed { id: quest_id });
+        }
+        if progress.progress < quest.objective.count() {
+            return Err(ContractError::QuestIncomplete { id: quest_id });
+        }
+        progress.claimed = true;
+        QUEST_PROGRESS.save(deps.storage, key, &progress)?;
+
+        let reward = quest.reward;
+        player.potions += reward.potions;
+        player.berries += reward.berries;
+        player.revives += reward.revives;
+        let mut level_up = None;
+        if reward.xp > 0 {
+            let index = roster_index(&player, player.default_pokemon)?;
+            level_up = gain_xp(deps.storage, &id, &mut player.pokemons[index], reward.xp)?;
+        }
+        PLAYERS.save(deps.storage, id.clone(), &player)?;
+
+        let mut response = Response::new();
+        let mut event = event("claim_quest")
+            .add_attribute("player", id.clone())
+            .add_attribute("sender", info.sender.clone())
+            .add_attribute("quest", quest_id)
+            .add_attribute("potions_delta", reward.potions.to_string())
+            .add_attribute("berries_delta", reward.berries.to_string())
+            .add_attribute("revives_delta", reward.revives.to_string())
+            .add_attribute("xp_delta", reward.xp.to_string());
+        if let Some(species) = reward.pokemon {
+            let data = SPECIES.load(deps.storage, species.clone())?;
+            take_supply(deps.storage, &data, &id)?;
+            let nft_address = species_collection(deps.storage, &data)?;
+            let token = next_token_id(deps.as_ref(), &nft_address)?;
+            let pokemon = Pokemon {
+                collection: nft_address,
+                token_id: token,
+                species: species.clone(),
+                index: player.pokemons.len() as i32,
+                health: MAX_HEALTH,
+                level: 1,
+                xp: 0,
+                original_trainer: id.clone(),
+                variant: None,
+                genes: roll_genes(&mut Rng::new(
+                    &env,
+                    &[id.as_bytes(), species.as_bytes(), &token.to_be_bytes()],
+                )),
+                moves: starting_moves(deps.storage, &data, 1)?,
+            };
+            response =
+                response.add_submessage(mint_msg(deps.storage, &info.sender, &id, pokemon, false)?);
+            event = event
+                .add_attribute("token_id", token.to_string())
+                .add_attribute("species", species);
+        }
+        Ok(response.add_event(event).add_events(level_up))
+    }
+
+    /// Pays the reward of a completed guild quest into the treasury.
+    fn claim_guild_quest(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        id: String,
+        quest_id: String,
+        quest: Quest,
+    ) -> ArchwayResult<ContractError> {
+        let (guild_id, mut guild, role) = load_membership(deps.storage, &id)?;
+        if role == GuildRole::Member {
+            return Err(ContractError::Unauthorized {});
+        }
+        let key = (guild_id.clone(), quest_id.clone());
+        let mut progress = GUILD_QUEST_PROGRESS
+            .may_load(deps.storage, key.clone())?
+            .unwrap_or_default();
+        if progress.claimed {
+            return Err(ContractError::QuestClaimed { id: quest_id });
+        }
+        if progress.progress < quest.objective.count() {
+            return Err(ContractError::QuestIncomplete { id: quest_id });
+        }
+        progress.claimed = true;
+        GUILD_QUEST_PROGRESS.save(deps.storage, key, &progress)?;
+
+        let reward = quest.reward;
+        for (kind, amount) in [
+            (ItemKind::Potion, reward.potions),
+            (ItemKind::Berry, reward.berries),
+            (ItemKind::Revive, reward.revives),
+        ] {
+            if amount > 0 {
+                guild.treasury.add_items(Items { kind, amount });
+            }
+        }
+        GUILDS.save(deps.storage, guild_id.clone(), &guild)?;
+        Ok(Response::new().add_event(
+            event("claim_quest")
+                .add_attribute("player", id)
+                .add_attribute("sender", info.sender)
+                .add_attribute("quest", quest_id)
+                .add_attribute("guild", guild_id)
+                .add_attribute("potions_delta", reward.potions.to_string())
+                .add_attribute("berries_delta", reward.berries.to_string())
+                .add_attribute("revives_delta", reward.revives.to_string()),
+        ))
+    }
+
+    pub fn create_guild(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        id: String,
+        guild_id: String,
+        name: String,
+        max_members: u32,
+    ) -> ArchwayResult<ContractError> {
+        load_owned_player(deps.storage, &id, &info.sender)?;
+        if PLAYER_GUILDS.has(deps.storage, id.clone()) {
+            return Err(ContractError::AlreadyInGuild { id });
+        }
+        if GUILDS.has(deps.storage, guild_id.clone()) {
+            return Err(ContractError::GuildTaken { id: guild_id });
+        }
+        if max_members == 0 || max_members > MAX_GUILD_MEMBERS {
+            return Err(ContractError::InvalidGuildSize {
+                max: MAX_GUILD_MEMBERS,
+            });
+        }
+        let guild = Guild {
+            name,
+            leader: id.clone(),
+            max_members,
+            members: 1,
+            treasury: Treasury::default(),
+        };
+        GUILDS.save(deps.storage, guild_id.clone(), &guild)?;
+        GUILD_MEMBERS.save(
+            deps.storage,
+            (guild_id.clone(), id.clone()),
+            &GuildRole::Leader,
+        )?;
+        PLAYER_GUILDS.save(deps.storage, id.clone(), &guild_id)?;
+        Ok(Response::new().add_event(
+            event("create_guild")
+                .add_attribute("player", id)
+                .add_attribute("sender", info.sender)
+                .add_attribute("guild", guild_id)
+                .add_attribute("max_members", max_members.to_string()),
+        ))
+    }
+
+    pub fn invite_to_guild(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        id: String,
+        player: String,
+    ) -> ArchwayResult<ContractError> {
+        load_owned_player(deps.storage, &id, &info.sender)?;
+        let (guild_id, _, role) = load_membership(deps.storage, &id)?;
+        if role == GuildRole::Member {
+            return Err(ContractError::Unauthorized {});
+        }
+        PLAYERS.load(deps.storage, player.clone())?;
+        GUILD_INVITES.save(deps.storage, (guild_id.clone(), player.clone()), &Empty {})?;
+        Ok(Response::new().add_event(
+            event("invite_to_guild")
+                .add_attribute("player", id)
+                .add_attribute("sender", info.sender)
+                .add_attribute("guild", guild_id)
+                .add_attribute("invitee", player),
+        ))
+    }
+
+    pub fn join_guild(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        id: String,
+        guild_id: String,
+    ) -> ArchwayResult<ContractError> {
+        load_owned_player(deps.storage, &id, &info.sender)?;
+        if PLAYER_GUILDS.has(deps.storage, id.clone()) {
+            return Err(ContractError::AlreadyInGuild { id });
+        }
+        let invite = (guild_id.clone(), id.clone());
+        if !GUILD_INVITES.has(deps.storage, invite.clone()) {
+            return Err(ContractError::NotInvited {
+                id,
+                guild: guild_id,
+            });
+        }
+        let Some(mut guild) = GUILDS.may_load(deps.storage, guild_id.clone())? else {
+            return Err(ContractError::UnknownGuild { id: guild_id });
+        };
+        if guild.members >= guild.max_members {
+            return Err(ContractError::GuildFull { id: guild_id });
+        }
+        GUILD_INVITES.remove(deps.storage, invite);
+        guild.members += 1;
+        GUILDS.save(deps.storage, guild_id.clone(), &guild)?;
+        GUILD_MEMBERS.save(
+            deps.storage,
+            (guild_id.clone(), id.clone()),
+            &GuildRole::Member,
+        )?;
+        PLAYER_GUILDS.save(deps.storage, id.clone(), &guild_id)?;
+        Ok(Response::new().add_event(
+            event("join_guild")
+                .add_attribute("player", id)
+                .add_attribute("sender", info.sender)
+                .add_attribute("guild", guild_id),
+        ))
+    }
+
+    /// Leaves the guild. The last member leaving disbands it, taking whatever the treasury holds.
+    pub fn leave_guild(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        id: String,
+    ) -> ArchwayResult<ContractError> {
+        let mut player = load_owned_player(deps.storage, &id, &info.sender)?;
+        let (guild_id, mut guild, role) = load_membership(deps.storage, &id)?;
+        if role == GuildRole::Leader && guild.members > 1 {
+            return Err(ContractError::LeaderCannotLeave {});
+        }
+        GUILD_MEMBERS.remove(deps.storage, (guild_id.clone(), id.clone()));
+        PLAYER_GUILDS.remove(deps.storage, id.clone());
+
+        let mut response = Response::new();
+        let mut event = event("leave_guild")
+            .add_attribute("player", id.clone())
+            .add_attribute("sender", info.sender)
+            .add_attribute("guild", guild_id.clone());
+        if role == GuildRole::Leader {
+            disband_guild(deps.storage, &guild_id)?;
+            for items in guild.treasury.items {
+                *player.items_mut(items.kind) += items.amount;
+            }
+            PLAYERS.save(deps.storage, id, &player)?;
+            if !guild.treasury.coins.is_empty() {
+                response = response.add_message(BankMsg::Send {
+                    to_address: player.address.to_string(),
+                    amount: guild.treasury.coins,
+                });
+            }
+            event = event.add_attribute("disbanded", "true");
+        } else {
+            guild.members -= 1;
+            GUILDS.save(deps.storage, guild_id, &guild)?;
+        }
+        Ok(response.add_event(event))
+    }
+
+    /// Removes the guild along with its pending invites, quest progress and standing on the
+    /// guild boards of the current season, so a guild founded under the same id starts afresh.
+    fn disband_guild(storage: &mut dyn Storage, guild_id: &str) -> StdResult<()> {
+        GUILDS.remove(storage, guild_id.to_string());
+        let invited = GUILD_INVITES
+            .prefix(guild_id.to_string())
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for player in invited {
+            GUILD_INVITES.remove(storage, (guild_id.to_string(), player));
+        }
+        let quests = GUILD_QUEST_PROGRESS
+            .prefix(guild_id.to_string())
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for quest in quests {
+            GUILD_QUEST_PROGRESS.remove(storage, (guild_id.to_string(), quest));
+        }
+        let season = SEASON.load(storage)?;
+        for kind in LeaderboardKind::ALL {
+            kind.guild_board().remove(storage, season, guild_id)?;
+        }
+        Ok(())
+    }
+
+    pub fn kick_from_guild(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        id: String,
+        player: String,
+    ) -> ArchwayResult<ContractError> {
+        load_owned_player(deps.storage, &id, &info.sender)?;
+        let (guild_id, mut guild, role) = load_membership(deps.storage, &id)?;
+        let target = member_role(deps.storage, &guild_id, &player)?;
+        let allowed = match role {
+            GuildRole::Leader => player != id,
+            GuildRole::Officer => target == GuildRole::Member,
+            GuildRole::Member => false,
+        };
+        if !allowed {
+            return Err(ContractError::Unauthorized {});
+        }
+        GUILD_MEMBERS.remove(deps.storage, (guild_id.clone(), player.clone()));
+        PLAYER_GUILDS.remove(deps.storage, player.clone());
+        guild.members -= 1;
+        GUILDS.save(deps.storage, guild_id.clone(), &guild)?;
+        Ok(Response::new().add_event(
+            event("kick_from_guild")
+                .add_attribute("player", id)
+                .add_attribute("sender", info.sender)
+                .add_attribute("guild", guild_id)
+                .add_attribute("member", player),
+        ))
+    }
+
+    pub fn set_guild_role(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        id: String,
+        player: String,
+        role: GuildRole,
+    ) -> ArchwayResult<ContractError> {
+        load_owned_player(deps.storage, &id, &info.sender)?;
+        let (guild_id, mut guild, current) = load_membership(deps.storage, &id)?;
+        member_role(deps.storage, &guild_id, &player)?;
+        if current != GuildRole::Leader || player == id {
+            return Err(ContractError::Unauthorized {});
+        }
+        GUILD_MEMBERS.save(deps.storage, (guild_id.clone(), player.clone()), &role)?;
+        if role == GuildRole::Leader {
+            GUILD_MEMBERS.save(
+                deps.storage,
+                (guild_id.clone(), id.clone()),
+                &GuildRole::Officer,
+            )?;
+            guild.leader = player.clone();
+            GUILDS.save(deps.storage, guild_id.clone(), &guild)?;
+        }
+        Ok(Response::new().add_event(
+            event("set_guild_role")
+                .add_attribute("player", id)
+                .add_attribute("sender", info.sender)
+                .add_attribute("guild", guild_id)
+                .add_attribute("member", player)
+                .add_attribute("role", role.as_str()),
+        ))
+    }
+
+    pub fn contribute_to_guild(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        id: String,
+        items: Vec<Items>,
+    ) -> ArchwayResult<ContractError> {
+        let mut player = load_owned_player(deps.storage, &id, &info.sender)?;
+        let (guild_id, mut guild, _) = load_membership(deps.storage, &id)?;
+        for contributed in &items {
+            if contributed.amount <= 0 {
+                return Err(ContractError::InvalidAmount {});
+            }
+            let held = player.items_mut(contributed.kind);
+            if *held < contributed.amount {
+                return Err(ContractError::NotEnoughItems {
+                    item: contributed.kind.as_str().to_string(),
+                });
+            }
+            *held -= contributed.amount;
+            guild.treasury.add_items(*contributed);
+        }
+        for coin in &info.funds {
+            add_coin(&mut guild.treasury.coins, coin.clone())?;
+        }
+        PLAYERS.save(deps.storage, id.clone(), &player)?;
+        GUILDS.save(deps.storage, guild_id.clone(), &guild)?;
+        Ok(Response::new().add_event(
+            event("contribute_to_guild")
+                .add_attribute("player", id)
+                .add_attribute("sender", info.sender)
+                .add_attribute("guild", guild_id)
+                .add_attribute("items", items_to_string(&items))
+                .add_attribute("funds", coins_to_string(&info.funds)),
+        ))
+    }
+
+    pub fn guild_payout(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        id: String,
+        player_id: String,
+        items: Vec<Items>,
+        coins: Vec<Coin>,
+    ) -> ArchwayResult<ContractError> {
+        load_owned_player(deps.storage, &id, &info.sender)?;
+        let (guild_id, mut guild, role) = load_membership(deps.storage, &id)?;
+        if role != GuildRole::Leader {
+            return Err(ContractError::Unauthorized {});
+        }
+        member_role(deps.storage, &guild_id, &player_id)?;
+        let mut player = PLAYERS.load(deps.storage, player_id.clone())?;
+        for paid in &items {
+            if paid.amount <= 0 {
+                return Err(ContractError::InvalidAmount {});
+            }
+            if !guild.treasury.take_items(*paid) {
+                return Err(ContractError::NotEnoughItems {
+                    item: paid.kind.as_str().to_string(),
+                });
+            }
+            *player.items_mut(paid.kind) += paid.amount;
+        }
+        for coin in &coins {
+            sub_coin(&mut guild.treasury.coins, coin.clone()).map_err(|_| {
+                ContractError::NotEnoughItems {
+                    item: coin.denom.clone(),
+                }
+            })?;
+        }
+        PLAYERS.save(deps.storage, player_id.clone(), &player)?;
+        GUILDS.save(deps.storage, guild_id.clone(), &guild)?;
+
+        let mut response = Response::new();
+        if !coins.is_empty() {
+            response = response.add_message(BankMsg::Send {
+                to_address: player.address.to_string(),
+                amount: coins.clone(),
+            });
+        }
+        Ok(response.add_event(
+            event("guild_payout")
+                .add_attribute("player", id)
+                .add_attribute("sender", info.sender)
+                .add_attribute("guild", guild_id)
+                .add_attribute("member", player_id)
+                .add_attribute("items", items_to_string(&items))
+                .add_attribute("coins", coins_to_string(&coins)),
+        ))
+    }
+
+    /// Loads the guild of a player along with the player's role in it.
+    fn load_membership(
+        storage: &dyn Storage,
+        id: &str,
+    ) -> Result<(String, Guild, GuildRole), ContractError> {
+        let Some(guild_id) = PLAYER_GUILDS.may_load(storage, id.to_string())? else {
+            return Err(ContractError::NotInGuild { id: id.to_string() });
+        };
+        let guild = GUILDS.load(storage, guild_id.clone())?;
+        let role = GUILD_MEMBERS.load(storage, (guild_id.clone(), id.to_string()))?;
+        Ok((guild_id, guild, role))
+    }
+
+    fn member_role(
+        storage: &dyn Storage,
+        guild: &str,
+        id: &str,
+    ) -> Result<GuildRole, ContractError> {
+        GUILD_MEMBERS
+            .may_load(storage, (guild.to_string(), id.to_string()))?
+            .ok_or_else(|| ContractError::NotInGuild { id: id.to_string() })
+    }
+
+    fn items_to_string(items: &[Items]) -> String {
+        items
+            .iter()
+            .map(|items| format!("{}{}", items.amount, items.kind.as_str()))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// The collection a species is minted into.
+    fn species_collection(storage: &dyn Storage, species: &Species) -> Result<Addr, ContractError> {
+        match &species.collection {
+            Some(id) => COLLECTIONS
+                .may_load(storage, id.clone())?
+                .ok_or_else(|| ContractError::UnknownCollection { id: id.clone() }),
+            None => Ok(NFT_CONTRACT.load(storage)?),
+        }
+    }
+
+    /// Picks the id for the next mint. Ids start at 1 and those the collection may already
+    /// hold are skipped, so a collection minted into before doesn't collide.
+    fn next_token_id(deps: Deps<ArchwayQuery>, nft_address: &Addr) -> StdResult<i32> {
+        let NumTokensResponse { count } = deps
+            .querier
+            .query_wasm_smart(nft_address, &Cw721QueryMsg::NumTokens {})?;
+        let last = TOKENS
+            .may_load(deps.storage, nft_address.clone())?
+            .unwrap_or_default();
+        i32::try_from(count)
+            .ok()
+            .and_then(|held| held.max(last).checked_add(1))
+            .ok_or_else(|| StdError::generic_err("no token ids left in the collection"))
+    }
+
+    /// Mints `pokemon` into its collection for `owner`, pointing its `token_uri` at the metadata
+    /// renderer, and parks it until the reply confirms the mint.
+    fn mint_msg(
+        storage: &mut dyn Storage,
+        owner: &Addr,
+        player: &str,
+        pokemon: Pokemon,
+        caught: bool,
+    ) -> StdResult<SubMsg<ArchwayMsg>> {
+        let token_uri = METADATA_RENDERER
+            .may_load(storage)?
+            .map(|base| format!("{}/{}/{}", base, pokemon.collection, pokemon.token_id));
+        let mint: Cw721ExecuteMsg = Cw721ExecuteMsg::Mint {
+            token_id: pokemon.token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri,
+            extension: TokenExtension {
+                publisher: "PokeArch".to_string(),
+            },
+        };
+        let wasm_msg = WasmMsg::Execute {
+            contract_addr: pokemon.collection.to_string(),
+            msg: to_json_binary(&mint)?,
+            funds: Vec::new(),
+        };
+        PENDING_MINT.save(
+            storage,
+            &PendingMint {
+                player: player.to_string(),
+                pokemon,
+                caught,
+            },
+        )?;
+        Ok(SubMsg::reply_on_success(wasm_msg, MINT_REPLY_ID))
+    }
+
+    /// Tells indexers to refresh the on-chain metadata of a token.
+    pub(crate) fn metadata_update(collection: &Addr, token_id: i32) -> Event {
+        event("metadata_update")
+            .add_attribute("collection", collection)
+            .add_attribute("token_id", token_id.to_string())
+    }
+
+    fn roll_genes(rng: &mut Rng) -> Genes {
+        let mut iv = || rng.below(MAX_IV as u64 + 1) as i32;
+        let ivs = Stats {
+            attack: iv(),
+            defense: iv(),
+            speed: iv(),
+        };
+        let nature = Nature::ALL[rng.below(Nature::ALL.len() as u64) as usize];
+        Genes { ivs, nature }
+    }
+
+    /// Each individual value and the nature come from either parent.
+    fn inherit_genes(rng: &mut Rng, first: &Genes, second: &Genes) -> Genes {
+        let mut pick = |first: i32, second: i32| match rng.below(2) {
+            0 => first,
+            _ => second,
+        };
+        let ivs = Stats {
+            attack: pick(first.ivs.attack, second.ivs.attack),
+            defense: pick(first.ivs.defense, second.ivs.defense),
+            speed: pick(first.ivs.speed, second.ivs.speed),
+        };
+        let nature = match rng.below(2) {
+            0 => first.nature,
+            _ => second.nature,
+        };
+        Genes { ivs, nature }
+    }
+
+    /// Shiny is rolled first, so a pokemon is never both.
+    fn roll_variant(rng: &mut Rng, odds: VariantOdds) -> Option<Variant> {
+        [
+            (Variant::Shiny, odds.shiny),
+            (Variant::Alternate, odds.alternate),
+        ]
+        .into_iter()
+        .find(|(_, one_in)| *one_in > 0 && rng.below((*one_in).into()) == 0)
+        .map(|(variant, _)| variant)
+    }
+
+    pub fn set_variant_odds(
+        deps: DepsMut<ArchwayQuery>,
+        info: MessageInfo,
+        odds: VariantOdds,
+    ) -> ArchwayResult<ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        VARIANT_ODDS.save(deps.storage, &odds)?;
+        Ok(Response::new().add_event(
+            event("set_variant_odds")
+                .add_attribute("sender", info.sender)
+                .add_attribute("shiny", odds.shiny.to_string())
+                .add_attribute("alternate", odds.alternate.to_string()),
+        ))
+    }
+
+    /// Checks that `player` may still get a pokemon of `species`.
+    fn ensure_supply(
+        storage: &dyn Storage,
+        species: &Species,
+        player: &str,
+    ) -> Result<(), ContractError> {
+        let Some(supply) = &species.supply else {
+            return Ok(());
+        };
+        let caught = SPECIES_CAUGHT
+            .may_load(storage, species.name.clone())?
+            .unwrap_or_default();
+        if caught >= supply.max {
+            return Err(ContractError::SoldOut {
+                species: species.name.clone(),
+            });
+        }
+        if let Some(max) = supply.per_player {
+            let key = (species.name.clone(), player.to_string());
+            if PLAYER_CAUGHT.may_load(storage, key)?.unwrap_or_default() >= max {
+                return Err(ContractError::CatchLimit {
+                    species: species.name.clone(),
+                    max,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Counts a new pokemon of `species` for `player`, against the supply if it has one.
+    fn take_supply(
+        storage: &mut dyn Storage,
+        species: &Species,
+        player: &str,
+    ) -> Result<(), ContractError> {
+        ensure_supply(storage, species, player)?;
+        let increment = |count: Option<u32>| -> StdResult<_> { Ok(count.unwrap_or_default() + 1) };
+        SPECIES_CAUGHT.update(storage, species.name.clone(), increment)?;
+        PLAYER_CAUGHT.update(
+            storage,
+            (species.name.clone(), player.to_string()),
+            increment,
+        )?;
+        Ok(())
+    }
+
+    fn ensure_badge(player: &Player, badge: &Option<String>) -> Result<(), ContractError> {
+        match badge {
+            Some(badge) if !player.badges.contains(badge) => Err(ContractError::MissingBadge {
+                badge: badge.clone(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Loads a player, checking that `sender` is the address that registered it.
+    fn load_owned_player(
+        storage: &dyn Storage,
+        id: &str,
+        sender: &Addr,
+    ) -> Result<Player, ContractError> {
+        let player = PLAYERS.load(storage, id.to_string())?;
+        if player.address != sender {
+            return Err(ContractError::NotPlayerOwner { id: id.to_string() });
+        }
+        Ok(player)
+    }
+
+    /// Checks that an unexpired ArchID domain resolves to `sender` or, if the domain collection
+    /// is configured, that `sender` owns the domain token.
+    fn verify_name(
+        deps: Deps<ArchwayQuery>,
+        env: &Env,
+        archid: &ArchId,
+        id: &str,
+        sender: &Addr,
+    ) -> Result<(), ContractError> {
+        // unknown names resolve to no address, so failing queries are errors of their own
+        let record: ResolveRecordResponse = deps.querier.query_wasm_smart(
+            &archid.registry,
+            &ArchIdQueryMsg::ResolveRecord {
+                name: id.to_string(),
+            },
+        )?;
+        if record.expiration <= env.block.time.seconds() {
+            return Err(ContractError::NameNotOwned { id: id.to_string() });
+        }
+        if record.address.as_deref() == Some(sender.as_str()) {
+            return Ok(());
+        }
+
+        if let Some(token) = &archid.token {
+            let owner: OwnerOfResponse = deps.querier.query_wasm_smart(
+                token,
+                &Cw721QueryMsg::OwnerOf {
+                    token_id: id.to_string(),
+                    include_expired: None,
+                },
+            )?;
+            if owner.owner == sender.as_str() {
+                return Ok(());
+            }
+        }
+        Err(ContractError::NameNotOwned { id: id.to_string() })
+    }
+
+    /// Converts a roster index into a position in `player.pokemons`, if it is in range.
+    fn roster_index(player: &Player, index: i32) -> Result<usize, ContractError> {
+        usize::try_from(index)
+            .ok()
+            .filter(|i| *i < player.pokemons.len())
+            .ok_or(ContractError::PokemonNotFound { index })
+    }
+
+    pub(crate) fn increment_score(
+        storage: &mut dyn Storage,
+        kind: LeaderboardKind,
+        id: &str,
+    ) -> StdResult<()> {
+        let season = SEASON.load(storage)?;
+        let board = kind.board();
+        let score = board.score(storage, season, id)?.unwrap_or(0);
+        board.set_score(storage, season, id, score + 1)?;
+        add_guild_score(storage, kind, id, 1)
+    }
+
+    /// Credits `amount` to the guild board of `id`'s guild, if they are in one.
+    fn add_guild_score(
+        storage: &mut dyn Storage,
+        kind: LeaderboardKind,
+        id: &str,
+        amount: i32,
+    ) -> StdResult<()> {
+        let Some(guild) = PLAYER_GUILDS.may_load(storage, id.to_string())? else {
+            return Ok(());
+        };
+        let season = SEASON.load(storage)?;
+        let board = kind.guild_board();
+        let score = board.score(storage, season, &guild)?.unwrap_or(0);
+        board.set_score(storage, season, &guild, score + amount)
+    }
+
+    /// Adds experience to one of `id`'s pokemon, returning a metadata update when it levels up.
+    fn gain_xp(
+        storage: &mut dyn Storage,
+        id: &str,
+        pokemon: &mut Pokemon,
+        xp: i32,
+    ) -> StdResult<Option<Event>> {
+        let previous_level = pokemon.level;
+        pokemon.xp += xp;
+        pokemon.level = 1 + pokemon.xp / XP_PER_LEVEL;
+
+        let season = SEASON.load(storage)?;
+        let board = LeaderboardKind::Level.board();
+        if board.score(storage, season, id)?.unwrap_or(0) < pokemon.level {
+            board.set_score(storage, season, id, pokemon.level)?;
+        }
+        if pokemon.level > previous_level {
+            add_guild_score(
+                storage,
+                LeaderboardKind::Level,
+                id,
+                pokemon.level - previous_level,
+            )?;
+        }
+        Ok((pokemon.level != previous_level)
+            .then(|| metadata_update(&pokemon.collection, pokemon.token_id)))
+    }
+
+    /// Something a player did that quests may be waiting for.
+    pub(crate) enum QuestAction<'a> {
+        /// Caught a pokemon of these types.
+        Catch(&'a [String]),
+        CollectBerries,
+        WinBattle,
+    }
+
+    /// Counts `action` towards every active quest of `id` it fits, returning an event for each
+    /// quest it completes.
+    pub(crate) fn advance_quests(
+        storage: &mut dyn Storage,
+        id: &str,
+        action: QuestAction,
+    ) -> StdResult<Vec<Event>> {
+        let quests = QUESTS
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        let guild = PLAYER_GUILDS.may_load(storage, id.to_string())?;
+        let mut events = vec![];
+        for (quest_id, quest) in quests {
+            let fits = match (&quest.objective, &action) {
+                (Objective::Catch { kind, .. }, QuestAction::Catch(types)) => {
+                    kind.as_ref().is_none_or(|kind| types.contains(kind))
+                }
+                (Objective::CollectBerries { .. }, QuestAction::CollectBerries) => true,
+                (Objective::WinBattles { .. }, QuestAction::WinBattle) => true,
+                _ => false,
+            };
+            if !quest.active || !fits {
+                continue;
+            }
+            let Some((progress_map, key)) = quest.progress_key(&quest_id, id, guild.as_deref())
+            else {
+                continue;
+            };
+            let mut progress = progress_map
+                .may_load(storage, key.clone())?
+                .unwrap_or_default();
+            if progress.claimed || progress.progress >= quest.objective.count() {
+                continue;
+            }
+            progress.progress += 1;
+            progress_map.save(storage, key, &progress)?;
+            if progress.progress == quest.objective.count() {
+                let mut event = event("quest_completed")
+                    .add_attribute("player", id)
+                    .add_attribute("quest", quest_id);
+                if quest.guild {
+                    event = event.add_attribute("guild", guild.clone().unwrap_or_default());
+                }
+                events.push(event);
+            }
+        }
+        Ok(events)
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut<ArchwayQuery>, env: Env, msg: Reply) -> ArchwayResult<ContractError> {
+    match msg.id {
+        WITHDRAW_REWARDS_REPLY_ID => reply::withdraw_rewards(deps, env, msg),
+        MINT_REPLY_ID => reply::mint(deps),
+        CREATE_COLLECTION_REPLY_ID => reply::create_collection(deps, msg),
+        id => Err(ContractError::UnknownReply(id)),
+    }
+}
+
+pub mod reply {
+    use archway_bindings::types::rewards::{ContractMetadataResponse, WithdrawRewardsResponse};
+    use cosmwasm_std::{QueryRequest, StdError};
+
+    use crate::state::{
+        LeaderboardKind, PendingMint, PENDING_COLLECTION, PENDING_MINT, SPECIES, TOKENS,
+        TOKEN_PLAYERS,
+    };
+
+    use super::execute::{advance_quests, increment_score, QuestAction};
+
+    use super::*;
+
+    /// Registers a collection the contract just instantiated.
+    pub fn create_collection(
+        deps: DepsMut<ArchwayQuery>,
+        msg: Reply,
+    ) -> ArchwayResult<ContractError> {
+        let response = msg.result.into_result().map_err(StdError::generic_err)?;
+        let addr = response
+            .events
+            .iter()
+            .filter(|event| event.ty == "instantiate")
+            .flat_map(|event| &event.attributes)
+            .find(|attr| attr.key == "_contract_address")
+            .ok_or_else(|| StdError::generic_err("missing collection address"))?;
+        let nft_address = deps.api.addr_validate(&addr.value)?;
+        let mut event = event("collection_created").add_attribute("contract", &nft_address);
+        match PENDING_COLLECTION.load(deps.storage)? {
+            Some(id) => {
+                COLLECTIONS.save(deps.storage, id.clone(), &nft_address)?;
+                event = event.add_attribute("collection", id);
+            }
+            None => NFT_CONTRACT.save(deps.storage, &nft_address)?,
+        }
+        PENDING_COLLECTION.remove(deps.storage);
+        Ok(Response::new().add_event(event))
+    }
+
+    /// Adds a freshly minted pokemon to its player's roster.
+    pub fn mint(deps: DepsMut<ArchwayQuery>) -> ArchwayResult<ContractError> {
+        let PendingMint {
+            player,
+            pokemon,
+            caught,
+        } = PENDING_MINT.load(deps.storage)?;
+        PENDING_MINT.remove(deps.storage);
+
+        let mut data = PLAYERS.load(deps.storage, player.clone())?;
+        TOKENS.save(deps.storage, pokemon.collection.clone(), &pokemon.token_id)?;
+        TOKEN_PLAYERS.save(
+            deps.storage,
+            (pokemon.collection.clone(), pokemon.token_id),
+            &player,
+        )?;
+        let species = SPECIES.load(deps.storage, pokemon.species.clone())?;
+        data.pokemons.push(pokemon);
+        PLAYERS.save(deps.storage, player.clone(), &data)?;
+        if !caught {
+            return Ok(Response::new());
+        }
+        increment_score(deps.storage, LeaderboardKind::Catches, &player)?;
+        let quests = advance_quests(deps.storage, &player, QuestAction::Catch(&species.types))?;
+        Ok(Response::new().add_events(quests))
+    }
+
+    /// Splits freshly withdrawn rewards between the gas tank, the prize pool and the treasury.
+    pub fn withdraw_rewards(
+        deps: DepsMut<ArchwayQuery>,
+        env: Env,
+        msg: Reply,
+    ) -> ArchwayResult<ContractError> {
+        let data = msg
+            .result
+            .into_result()
+            .map_err(StdError::generic_err)?
+            .data
+            .ok_or_else(|| StdError::generic_err("missing withdraw rewards response"))?;
+        let withdrawn: WithdrawRewardsResponse = from_json(&data)?;
+
+        // rewards are paid to the rewards address, so only split what reached the contract
+        let metadata: ContractMetadataResponse =
+            deps.querier
+                .query(&QueryRequest::Custom(ArchwayQuery::contract_metadata(
+                    env.contract.address.clone(),
+                )))?;
+        if metadata.rewards_address != env.contract.address.as_str() {
+            return Ok(Response::new().add_event(
+                event("split_rewards")
+                    .add_attribute("records", withdrawn.records_num.to_string())
+                    .add_attribute("rewards_address", metadata.rewards_address),
+            ));
+        }
+
+        let split = REWARDS_SPLIT.load(deps.storage)?;
+        let mut ledger = REWARDS_LEDGER.load(deps.storage)?;
+        let mut gas_tank = GAS_TANK.load(deps.storage)?;
+        let mut prize_pool = PRIZE_POOL.load(deps.storage)?;
+        let mut treasury = vec![];
+        for coin in withdrawn.total_rewards {
+            let to_gas_tank = coin.amount.mul_floor(split.gas_tank);
+            let to_prize_pool = coin.amount.mul_floor(split.prize_pool);
+            let to_treasury = coin.amount - to_gas_tank - to_prize_pool;
+
+            add_coin(&mut ledger.withdrawn, coin.clone())?;
+            for (amount, balances) in [
+                (to_gas_tank, vec![&mut ledger.gas_tank, &mut gas_tank]),
+                (to_prize_pool, vec![&mut ledger.prize_pool, &mut prize_pool]),
+                (to_treasury, vec![&mut ledger.treasury, &mut treasury]),
+            ] {
+                for balance in balances {
+                    add_coin(balance, Coin::new(amount.u128(), &coin.denom))?;
+                }
+            }
+        }
+        REWARDS_LEDGER.save(deps.storage, &ledger)?;
+        GAS_TANK.save(deps.storage, &gas_tank)?;
+        PRIZE_POOL.save(deps.storage, &prize_pool)?;
+
+        let event = event("split_rewards")
+            .add_attribute("records", withdrawn.records_num.to_string())
+            .add_attribute("treasury", split.treasury.clone())
+            .add_attribute("treasury_amount", coins_to_string(&treasury));
+        let mut response = Response::new().add_event(event);
+        if !treasury.is_empty() {
+            response = response.add_message(BankMsg::Send {
+                to_address: split.treasury.to_string(),
+                amount: treasury,
+            });
+        }
+        Ok(response)
+    }
+}
+
+/// Adds a coin to a list of coins, merging it with the coin of the same denom.
+fn add_coin(coins: &mut Vec<Coin>, coin: Coin) -> StdResult<()> {
+    let mut sum = Coins::try_from(std::mem::take(coins))?;
+    sum.add(coin)?;
+    *coins = sum.into_vec();
+    Ok(())
+}
+
+/// Formats coins the way the SDK does, e.g. `10uarch,5uconst`.
+fn coins_to_string(coins: &[Coin]) -> String {
+    coins
+        .iter()
+        .map(Coin::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Subtracts a coin from a list of coins, failing if there isn't enough of its denom.
+fn sub_coin(coins: &mut Vec<Coin>, coin: Coin) -> StdResult<()> {
+    let mut rest = Coins::try_from(std::mem::take(coins))?;
+    rest.sub(coin)?;
+    *coins = rest.into_vec();
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps<ArchwayQuery>, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::CheckAllowance { addr } => to_json_binary(&query::check_allowance(deps, addr)?),
+        QueryMsg::GetPlayer { id } => to_json_binary(&query::get_player(deps, id)?),
+        QueryMsg::GetSpecies { name } => to_json_binary(&query::get_species(deps, name)?),
+        QueryMsg::VariantOdds {} => to_json_binary(&VARIANT_ODDS.load(deps.storage)?),
+        QueryMsg::Genes {
+            collection,
+            token_id,
+        } => to_json_binary(&query::genes(deps, collection, token_id)?),
+        QueryMsg::VariantCounts { species } => {
+            to_json_binary(&query::variant_counts(deps, species)?)
+        }
+        QueryMsg::Supply { species, player } => {
+            to_json_binary(&query::supply(deps, species, player)?)
+        }
+        QueryMsg::GetStarters {} => to_json_binary(&query::get_starters(deps)?),
+        QueryMsg::CheckInSchedule {} => to_json_binary(&CHECK_IN.load(deps.storage)?),
+        QueryMsg::BreedingConfig {} => to_json_binary(&BREEDING.load(deps.storage)?),
+        QueryMsg::Eggs { id } => to_json_binary(&query::eggs(deps, env, id)?),
+        QueryMsg::Breeding {
+            collection,
+            token_id,
+        } => to_json_binary(&query::breeding(deps, collection, token_id)?),
+        QueryMsg::GetNFTContract {} => to_json_binary(&NFT_CONTRACT.load(deps.storage)?),
+        QueryMsg::Collections {} => to_json_binary(&query::collections(deps)?),
+        QueryMsg::PokemonMetadata {
+            collection,
+            token_id,
+        } => to_json_binary(&query::pokemon_metadata(deps, collection, token_id)?),
+        QueryMsg::TokenMetadata {
+            collection,
+            token_id,
+            data_uri,
+        } => to_json_binary(&query::token_metadata(
+            deps,
+            collection,
+            token_id,
+            data_uri.unwrap_or(false),
+        )?),
+        QueryMsg::Gym { id } => to_json_binary(&GYMS.load(deps.storage, id)?),
+        QueryMsg::Gyms {} => to_json_binary(&query::gyms(deps)?),
+        QueryMsg::Region { id } => to_json_binary(&REGIONS.load(deps.storage, id)?),
+        QueryMsg::Regions {} => to_json_binary(&query::regions(deps)?),
+        QueryMsg::Move { id } => to_json_binary(&MOVES.load(deps.storage, id)?),
+        QueryMsg::Moves {} => to_json_binary(&query::moves(deps)?),
+        QueryMsg::ActiveQuests {} => to_json_binary(&query::active_quests(deps)?),
+        QueryMsg::QuestProgress { id } => to_json_binary(&query::quest_progress(deps, id)?),
+        QueryMsg::PendingRewards {} => to_json_binary(&query::pending_rewards(deps, env)?),
+        QueryMsg::Rewards {} => to_json_binary(&query::rewards(deps)?),
+        QueryMsg::Premium {} => to_json_binary(&PREMIUM.load(deps.storage)?),
+        QueryMsg::PauseStatus {} => to_json_binary(&PauseStatusResponse {
+            paused: PAUSED.load(deps.storage)?,
+            disabled: DISABLED_FEATURES.load(deps.storage)?,
+        }),
+        QueryMsg::Leaderboard {
+            board,
+            season,
+            start_after,
+            limit,
+        } => to_json_binary(&query::leaderboard(
+            deps,
+            board.board(),
+            season,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::Guild { guild } => to_json_binary(&query::guild(deps, guild)?),
+        QueryMsg::PlayerGuild { id } => to_json_binary(&PLAYER_GUILDS.may_load(deps.storage, id)?),
+        QueryMsg::GuildLeaderboard {
+            board,
+            season,
+            start_after,
+            limit,
+        } => to_json_binary(&query::leaderboard(
+            deps,
+            board.guild_board(),
+            season,
+            start_after,
+            limit,
+        )?),
+    }
+}
+
+pub mod query {
+
+    use archway_bindings::types::rewards::RewardsRecordsResponse;
+    use cosmwasm_std::{Order, QueryRequest, StdError};
+
+    use crate::metadata::TokenMetadata;
+    use crate::msg::{
+        BreedingResponse, CollectionEntry, CollectionsResponse, EggEntry, GuildMember,
+        GuildResponse, GymEntry, LeaderboardEntry, LeaderboardResponse, MoveEntry,
+        PendingRewardsResponse, PlayerResponse, PokemonMetadata, QuestEntry, QuestProgressEntry,
+        RegionEntry, RewardsResponse, SupplyResponse, TokenMetadataResponse, VariantCountsResponse,
+    };
+    use crate::state::{Leaderboard, Pokemon, Species, QUESTS, SPECIES, STARTERS, TOKEN_PLAYERS};
+
+    use super::*;
+
+    pub fn check_allowance(deps: Deps<ArchwayQuery>, addr: String) -> StdResult<bool> {
+        Ok(ALLOWED_ADDRESSES.has(deps.storage, deps.api.addr_validate(&addr)?))
+    }
+    pub fn get_player(deps: Deps<ArchwayQuery>, id: String) -> StdResult<PlayerResponse> {
+        let player = PLAYERS.load(deps.storage, id)?;
+        Ok(PlayerResponse { player })
+    }
+
+    pub fn get_species(deps: Deps<ArchwayQuery>, name: String) -> StdResult<Species> {
+        SPECIES.load(deps.storage, name)
+    }
+
+    pub fn get_starters(deps: Deps<ArchwayQuery>) -> StdResult<Vec<String>> {
+        Ok(STARTERS.may_load(deps.storage)?.unwrap_or_default())
+    }
+
+    /// Sums the first page of rewards records credited to this contract.
+    pub fn pending_rewards(
+        deps: Deps<ArchwayQuery>,
+        env: Env,
+    ) -> StdResult<PendingRewardsResponse> {
+        let response: RewardsRecordsResponse =
+            deps.querier
+                .query(&QueryRequest::Custom(ArchwayQuery::rewards_records(
+                    env.contract.address,
+                )))?;
+        let mut rewards = vec![];
+        for record in &response.records {
+            for coin in &record.rewards {
+                add_coin(&mut rewards, coin.clone())?;
+            }
+        }
+        Ok(PendingRewardsResponse {
+            records: response.records.len() as u64,
+            rewards,
+        })
+    }
+
+    pub fn collections(deps: Deps<ArchwayQuery>) -> StdResult<CollectionsResponse> {
+        let collections = COLLECTIONS
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(id, address)| CollectionEntry { id, address }))
+            .collect::<StdResult<_>>()?;
+        Ok(CollectionsResponse {
+            default: NFT_CONTRACT.may_load(deps.storage)?,
+            collections,
+        })
+    }
+
+    /// Finds a minted pokemon in the roster of the player holding it.
+    fn minted_pokemon(
+        deps: Deps<ArchwayQuery>,
+        collection: &Addr,
+        token_id: i32,
+    ) -> StdResult<(String, Pokemon)> {
+        let trainer = TOKEN_PLAYERS.load(deps.storage, (collection.clone(), token_id))?;
+        let pokemon = PLAYERS
+            .load(deps.storage, trainer.clone())?
+            .pokemons
+            .into_iter()
+            .find(|pokemon| pokemon.collection == *collection && pokemon.token_id == token_id)
+            .ok_or_else(|| StdError::not_found("pokemon"))?;
+        Ok((trainer, pokemon))
+    }
+
+    pub fn pokemon_metadata(
+        deps: Deps<ArchwayQuery>,
+        collection: String,
+        token_id: i32,
+    ) -> StdResult<PokemonMetadata> {
+        let collection = deps.api.addr_validate(&collection)?;
+        let (trainer, pokemon) = minted_pokemon(deps, &collection, token_id)?;
+        let species = SPECIES.load(deps.storage, pokemon.species.clone())?;
+        Ok(PokemonMetadata {
+            species: species.name,
+            types: species.types,
+            level: pokemon.level,
+            xp: pokemon.xp,
+            stats: pokemon.stats(&species.base_stats),
+            original_trainer: pokemon.original_trainer,
+            variant: pokemon.variant,
+            trainer,
+        })
+    }
+
+    pub fn token_metadata(
+        deps: Deps<ArchwayQuery>,
+        collection: Option<String>,
+        token_id: i32,
+        data_uri: bool,
+    ) -> StdResult<TokenMetadataResponse> {
+        let collection = match collection {
+            Some(collection) => deps.api.addr_validate(&collection)?,
+            None => NFT_CONTRACT.load(deps.storage)?,
+        };
+        let (_, pokemon) = minted_pokemon(deps, &collection, token_id)?;
+        let species = SPECIES.load(deps.storage, pokemon.species.clone())?;
+        let metadata = TokenMetadata::new(&species, &pokemon);
+        let token_uri = match data_uri {
+            true => Some(metadata.to_data_uri()?),
+            false => None,
+        };
+        Ok(TokenMetadataResponse {
+            metadata,
+            token_uri,
+        })
+    }
+
+    pub fn genes(
+        deps: Deps<ArchwayQuery>,
+        collection: Option<String>,
+        token_id: i32,
+    ) -> StdResult<Genes> {
+        let collection = match collection {
+            Some(collection) => deps.api.addr_validate(&collection)?,
+            None => NFT_CONTRACT.load(deps.storage)?,
+        };
+        let (_, pokemon) = minted_pokemon(deps, &collection, token_id)?;
+        Ok(pokemon.genes)
+    }
+
+    pub fn eggs(deps: Deps<ArchwayQuery>, env: Env, id: String) -> StdResult<Vec<EggEntry>> {
+        let config = BREEDING.load(deps.storage)?;
+        EGGS.prefix(id)
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| {
+                item.map(|(id, egg)| EggEntry {
+                    id,
+                    ready: egg.is_ready(&config, env.block.height),
+                    egg,
+                })
+            })
+            .collect()
+    }
+
+    pub fn breeding(
+        deps: Deps<ArchwayQuery>,
+        collection: Option<String>,
+        token_id: i32,
+    ) -> StdResult<BreedingResponse> {
+        let collection = match collection {
+            Some(collection) => deps.api.addr_validate(&collection)?,
+            None => NFT_CONTRACT.load(deps.storage)?,
+        };
+        let key = (collection, token_id);
+        Ok(BreedingResponse {
+            ready_at: BREEDING_COOLDOWNS.may_load(deps.storage, key.clone())?,
+            parents: LINEAGE.may_load(deps.storage, key)?.unwrap_or_default(),
+        })
+    }
+
+    pub fn supply(
+        deps: Deps<ArchwayQuery>,
+        species: String,
+        player: Option<String>,
+    ) -> StdResult<SupplyResponse> {
+        let supply = SPECIES.load(deps.storage, species.clone())?.supply;
+        let caught = SPECIES_CAUGHT
+            .may_load(deps.storage, species.clone())?
+            .unwrap_or_default();
+        let per_player = supply.as_ref().and_then(|supply| supply.per_player);
+        let player_remaining = match (per_player, player) {
+            (Some(max), Some(player)) => {
+                let caught = PLAYER_CAUGHT
+                    .may_load(deps.storage, (species, player))?
+                    .unwrap_or_default();
+                Some(max.saturating_sub(caught))
+            }
+            _ => None,
+        };
+        Ok(SupplyResponse {
+            caught,
+            remaining: supply.map(|supply| supply.max.saturating_sub(caught)),
+            player_remaining,
+        })
+    }
+
+    pub fn variant_counts(
+        deps: Deps<ArchwayQuery>,
+        species: Option<String>,
+    ) -> StdResult<VariantCountsResponse> {
+        let count = |variant: Variant| -> StdResult<u32> {
+            let variant = variant.as_str().to_string();
+            match &species {
+                Some(species) => Ok(VARIANTS_CAUGHT
+                    .may_load(deps.storage, (variant, species.clone()))?
+                    .unwrap_or_default()),
+                None => VARIANTS_CAUGHT
+                    .prefix(variant)
+                    .range(deps.storage, None, None, Order::Ascending)
+                    .map(|item| item.map(|(_, count)| count))
+                    .sum(),
+            }
+        };
+        Ok(VariantCountsResponse {
+            shiny: count(Variant::Shiny)?,
+            alternate: count(Variant::Alternate)?,
+        })
+    }
+
+    pub fn regions(deps: Deps<ArchwayQuery>) -> StdResult<Vec<RegionEntry>> {
+        REGIONS
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(id, region)| RegionEntry { id, region }))
+            .collect()
+    }
+
+    pub fn moves(deps: Deps<ArchwayQuery>) -> StdResult<Vec<MoveEntry>> {
+        MOVES
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(id, details)| MoveEntry { id, details }))
+            .collect()
+    }
+
+    pub fn gyms(deps: Deps<ArchwayQuery>) -> StdResult<Vec<GymEntry>> {
+        GYMS.range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(id, gym)| GymEntry { id, gym }))
+            .collect()
+    }
+
+    pub fn active_quests(deps: Deps<ArchwayQuery>) -> StdResult<Vec<QuestEntry>> {
+        QUESTS
+            .range(deps.storage, None, None, Order::Ascending)
+            .filter(|item| item.as_ref().map_or(true, |(_, quest)| quest.active))
+            .map(|item| item.map(|(id, quest)| QuestEntry { id, quest }))
+            .collect()
+    }
+
+    pub fn quest_progress(
+        deps: Deps<ArchwayQuery>,
+        id: String,
+    ) -> StdResult<Vec<QuestProgressEntry>> {
+        let guild = PLAYER_GUILDS.may_load(deps.storage, id.clone())?;
+        let mut entries = vec![];
+        for item in QUESTS.range(deps.storage, None, None, Order::Ascending) {
+            let (quest_id, quest) = item?;
+            // guild quests are tracked for the player's guild, if they have one
+            let Some((progress_map, key)) = quest.progress_key(&quest_id, &id, guild.as_deref())
+            else {
+                continue;
+            };
+            let progress = progress_map
+                .may_load(deps.storage, key)?
+                .unwrap_or_default();
+            // inactive quests only show up while a reward is waiting to be claimed
+            let claimable = !progress.claimed && progress.progress >= quest.objective.count();
+            if !quest.active && !claimable {
+                continue;
+            }
+            entries.push(QuestProgressEntry {
+                quest: quest_id,
+                progress: progress.progress,
+                goal: quest.objective.count(),
+                claimed: progress.claimed,
+            });
+        }
+        Ok(entries)
+    }
+
+    pub fn rewards(deps: Deps<ArchwayQuery>) -> StdResult<RewardsResponse> {
+        Ok(RewardsResponse {
+            split: REWARDS_SPLIT.load(deps.storage)?,
+            ledger: REWARDS_LEDGER.load(deps.storage)?,
+            gas_tank: GAS_TANK.load(deps.storage)?,
+            prize_pool: PRIZE_POOL.load(deps.storage)?,
+        })
+    }
+
+    pub fn guild(deps: Deps<ArchwayQuery>, id: String) -> StdResult<GuildResponse> {
+        let guild = GUILDS.load(deps.storage, id.clone())?;
+        let members = GUILD_MEMBERS
+            .prefix(id)
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|member| member.map(|(player, role)| GuildMember { player, role }))
+            .collect::<StdResult<_>>()?;
+        Ok(GuildResponse { guild, members })
+    }
+
+    /// Ranks players or guilds, depending on the board. Guilds are listed under their ids.
+    pub fn leaderboard(
+        deps: Deps<ArchwayQuery>,
+        board: Leaderboard,
+        season: Option<u32>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<LeaderboardResponse> {
+        let season = match season {
+            Some(season) => season,
+            None => SEASON.load(deps.storage)?,
+        };
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        let start_after = match start_after {
+            Some(id) => board
+                .score(deps.storage, season, &id)?
+                .map(|score| (score, id)),
+            None => None,
+        };
+        let entries = board
+            .top(deps.storage, season, start_after, limit)?
+            .into_iter()
+            .map(|(player, score)| LeaderboardEntry { player, score })
+            .collect();
+        Ok(LeaderboardResponse { season, entries })
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut<ArchwayQuery>, env: Env, msg: SudoMsg) -> ArchwayResult<ContractError> {
+    match msg {
+        SudoMsg::CwGrant(grant) => process_grant(deps, env, grant),
+    }
+}
+
+fn process_grant(
+    deps: DepsMut<ArchwayQuery>,
+    env: Env,
+    grant: CwGrant,
+) -> ArchwayResult<ContractError> {
+    const TYPE_URL: &str = "cosmwasm.wasm.v1.MsgExecuteContract";
+
+    execute::ensure_enabled(deps.storage, Some(Feature::FeeGrants))?;
+
+    let premium = PREMIUM.load(deps.storage)?;
+    let exclude_premium =
+        premium.flat_fee.is_some() && premium.grant_policy == GrantPolicy::Exclude;
+
+    for msg in grant.msgs {
+        // we check if all the senders are in the allow list
+        let addr = deps.api.addr_validate(&msg.sender)?;
+        if !ALLOWED_ADDRESSES.has(deps.storage, addr) {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        // we check the message type url
+        if msg.type_url != TYPE_URL {
+            return Err(ContractError::DisallowedMessage(msg.type_url));
+        }
+
+        // premium actions on this contract may have to be paid by the players themselves, the
+        // rest keep being sponsored even though the flat fee applies to them too. A message
+        // that doesn't parse fails on execution anyway, so it isn't a premium one
+        if exclude_premium {
+            let execute: MsgExecuteContract = msg.try_into_proto()?;
+            if execute.contract == env.contract.address.as_str()
+                && from_json::<ExecuteMsg>(&execute.msg).is_ok_and(|msg| msg.is_premium())
+            {
+                return Err(ContractError::PremiumNotGranted {});
+            }
+        }
+    }
+
+    // grants are paid out of the gas tank, which is filled with developer rewards
+    let mut gas_tank = GAS_TANK.load(deps.storage)?;
+    for fee in grant.fee_requested {
+        sub_coin(&mut gas_tank, fee).map_err(|_| ContractError::GasTankEmpty {})?;
+    }
+    GAS_TANK.save(deps.storage, &gas_tank)?;
+
+    Ok(Response::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use andromeda_non_fungible_tokens::cw721::TokenExtension;
+
+    use archway_bindings::types::rewards::WithdrawRewardsResponse;
+
+    use crate::metadata::TokenMetadata;
+    use crate::msg::{
+        BreedingResponse, EggEntry, GuildResponse, LeaderboardEntry, LeaderboardResponse,
+        MoveEntry, PendingRewardsResponse, PlayerResponse, PokemonMetadata, QuestEntry,
+        QuestProgressEntry, RegionEntry, RewardsResponse, SupplyResponse, TokenMetadataResponse,
+        VariantCountsResponse,
+    };
+    use crate::state::{
+        GuildRole, Gym, GymPokemon, LeaderboardKind, LearnableMove, Move, Nature, Objective,
+        Player, Pokemon, Quest, QuestReward, Region, Spawn, Species, Stats, Supply, TimeWindow,
+        Variant, TOKENS,
+    };
+
+    use super::*;
+    use archway_bindings::types::rewards::{
+        ContractMetadataResponse, RewardsRecord, RewardsRecordsResponse,
+    };
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{
+        coin, coins, from_json, Addr, Attribute, ContractResult, OwnedDeps, SubMsgResponse,
+        SubMsgResult, SystemResult, Timestamp,
+    };
+    use cw721::NumTokensResponse;
+
+    type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier<ArchwayQuery>, ArchwayQuery>;
+
+    /// Mock dependencies whose chain reports two pending rewards records for the contract and
+    /// whose collection reports `num_tokens` tokens.
+    fn mock_dependencies_with_tokens(num_tokens: u64) -> MockDeps {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(move |_| {
+            let response = NumTokensResponse { count: num_tokens };
+            SystemResult::Ok(to_json_binary(&response).into())
+        });
+        deps
+    }
+
+    fn mock_dependencies() -> MockDeps {
+        mock_dependencies_paying("cosmos2contract")
+    }
+
+    /// Mock dependencies whose developer rewards are paid out to `rewards_address`.
+    fn mock_dependencies_paying(rewards_address: &'static str) -> MockDeps {
+        let querier = MockQuerier::new(&[]).with_custom_handler(move |query| {
+            let response = match query {
+                ArchwayQuery::ContractMetadata { .. } => {
+                    to_json_binary(&ContractMetadataResponse {
+                        owner_address: "cosmos2contract".to_string(),
+                        rewards_address: rewards_address.to_string(),
+                    })
+                }
+                ArchwayQuery::RewardsRecords { .. } => {
+                    let record = |id, amount| RewardsRecord {
+                        id,
+                        rewards_address: "cosmos2contract".to_string(),
+                        rewards: coins(amount, "aarch"),
+                        calculated_height: 100,
+                        calculated_time: "2023-01-01T00:00:00Z".to_string(),
+                    };
+                    to_json_binary(&RewardsRecordsResponse {
+                        records: vec![record(1, 300), record(2, 700)],
+                        pagination: None,
+                    })
+                }
+                _ => return SystemResult::Ok(ContractResult::Err("unsupported query".to_string())),
+            };
+            SystemResult::Ok(response.into())
+        });
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier,
+            custom_query_type: std::marker::PhantomData,
+        };
+        deps.querier.update_wasm(|_| {
+            SystemResult::Ok(to_json_binary(&NumTokensResponse { count: 0 }).into())
+        });
+        deps
+    }
+
+    /// Runs `super::execute` and confirms the mints it sends, the way the chain would.
+    fn execute(
+        mut deps: DepsMut<ArchwayQuery>,
+        env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> ArchwayResult<ContractError> {
+        let res = super::execute(deps.branch(), env.clone(), info, msg)?;
+        for msg in res.messages.iter().filter(|msg| msg.id == MINT_REPLY_ID) {
+            let result = SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            });
+            reply(deps.branch(), env.clone(), Reply { id: msg.id, result })?;
+        }
+        Ok(res)
+    }
+
+    /// Registers a few species as `creator` and offers the first three as starters.
+    fn add_species(deps: &mut MockDeps) {
+        for name in ["bulbasaur", "charmander", "squirtle", "pidgey"] {
+            let msg = ExecuteMsg::SetSpecies {
+                species: Species {
+                    name: name.to_string(),
+                    types: vec!["normal".to_string()],
+                    image: Some(format!("ipfs://{name}")),
+                    collection: None,
+                    base_stats: Stats {
+                        attack: 50,
+                        defense: 50,
+                        speed: 50,
+                    },
+                    required_badge: None,
+                    supply: None,
+                    variant_odds: None,
+                    learnset: vec![],
+                },
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+        let msg = ExecuteMsg::SetStarters {
+            starters: vec![
+                "bulbasaur".to_string(),
+                "charmander".to_string(),
+                "squirtle".to_string(),
+            ],
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    /// Instantiates the contract as `creator`, points it at the `nft` collection and adds species.
+    fn setup(deps: &mut MockDeps) {
+        let info = mock_info("creator", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InstantiateMsg { collection: None },
+        )
+        .unwrap();
+        let msg = ExecuteMsg::SetNFTContract {
+            addr: "nft".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        add_species(deps);
+    }
+
+    /// Makes `species` the only spawn of a region named after it.
+    fn spawn_only(deps: &mut MockDeps, species: &str) {
+        let msg = ExecuteMsg::SetRegion {
+            id: species.to_string(),
+            region: Region {
+                name: species.to_string(),
+                spawns: vec![Spawn {
+                    species: species.to_string(),
+                    weight: 1,
+                    min_level: 1,
+                    max_level: 1,
+                    window: None,
+                }],
+                required_badge: None,
+            },
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    /// Genes of the pokemon minted as `token_id` into the default collection.
+    fn genes(deps: &MockDeps, token_id: i32) -> Genes {
+        let msg = QueryMsg::Genes {
+            collection: None,
+            token_id,
+        };
+        from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+    }
+
+    /// Has `id` run into a wild `species`, so that it can be caught.
+    fn encounter(deps: &mut MockDeps, id: &str, species: &str) {
+        spawn_only(deps, species);
+        let msg = ExecuteMsg::Explore {
+            id: id.to_string(),
+            region: species.to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn proper_initialization() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg { collection: None };
+        let info = mock_info("creator", &coins(1000, "earth"));
+
+        // we can just call .unwrap() to assert this was a success
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(1, res.messages.len());
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::CheckAllowance {
+                addr: Addr::unchecked("creator").to_string(),
+            },
+        )
+        .unwrap();
+        let value: bool = from_json(&res).unwrap();
+        assert!(value);
+
+        // the instantiation funds fill the gas tank
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Rewards {}).unwrap();
+        let rewards: RewardsResponse = from_json(res).unwrap();
+        assert_eq!(coins(1000, "earth"), rewards.gas_tank);
+    }
+
+    #[test]
+    fn set_nft_address() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg { collection: None };
+        let info = mock_info("creator", &coins(1000, "earth"));
+
+        // we can just call .unwrap() to assert this was a success
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::SetNFTContract {
+            addr: Addr::unchecked("nft").to_string(),
+        };
+        let info = mock_info("creator", &[]);
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert!(res.messages.is_empty());
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetNFTContract {}).unwrap();
+        assert_eq!(Addr::unchecked("nft"), from_json::<Addr>(res).unwrap());
+    }
+
+    #[test]
+    fn create_collection() {
+        let mut deps = mock_dependencies();
+        let collection = NewCollection {
+            id: None,
+            code_id: 7,
+            name: "PokeArch".to_string(),
+            symbol: "POKE".to_string(),
+            kernel_address: "kernel".to_string(),
+        };
+        let msg = InstantiateMsg {
+            collection: Some(collection.clone()),
+        };
+        let res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(CREATE_COLLECTION_REPLY_ID, res.messages[1].id);
+
+        let msg = ExecuteMsg::CreateCollection(collection);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            msg.clone(),
+        );
+        assert!(matches!(err, Err(ContractError::Unauthorized {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let instantiate = Cw721InstantiateMsg {
+            name: "PokeArch".to_string(),
+            symbol: "POKE".to_string(),
+            minter: AndrAddr::from_string(mock_env().contract.address),
+            kernel_address: "kernel".to_string(),
+            owner: Some("creator".to_string()),
+            modules: None,
+        };
+        assert_eq!(
+            CosmosMsg::Wasm(WasmMsg::Instantiate {
+                admin: Some("creator".to_string()),
+                code_id: 7,
+                msg: to_json_binary(&instantiate).unwrap(),
+                funds: vec![],
+                label: "PokeArch".to_string(),
+            }),
+            res.messages[0].msg
+        );
+
+        // the reply picks the new address out of the instantiate event
+        let result = SubMsgResult::Ok(SubMsgResponse {
+            events: vec![Event::new("instantiate").add_attribute("_contract_address", "nft")],
+            data: None,
+        });
+        let msg = Reply {
+            id: CREATE_COLLECTION_REPLY_ID,
+            result,
+        };
+        reply(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(
+            Addr::unchecked("nft"),
+            NFT_CONTRACT.load(&deps.storage).unwrap()
+        );
+    }
+
+    #[test]
+    fn add_allowance() {
+        let mut deps = mock_dependencies();
+        setup(&mut deps);
+        let msg = ExecuteMsg::AddAllowance(Addr::unchecked("sender").to_string());
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            msg.clone(),
+        );
+        assert!(matches!(err, Err(ContractError::Unauthorized {})));
+        let msg_remove = ExecuteMsg::RemoveAllowance(Addr::unchecked("sender").to_string());
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            msg_remove,
+        );
+        assert!(matches!(err, Err(ContractError::Unauthorized {})));
+        let info = mock_info("creator", &[]);
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::CheckAllowance {
+                addr: Addr::unchecked("sender").to_string(),
+            },
+        )
+        .unwrap();
+        let value: bool = from_json(&res).unwrap();
+        assert!(value);
+    }
+
+    #[test]
+    fn register() {
+        let mut deps = mock_dependencies();
+        setup(&mut deps);
+
+        let msg = ExecuteMsg::Register {
+            id: "hello.arch".to_string(),
+            starter: "charmander".to_string(),
+        };
+        let info = mock_info("sender", &[]);
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(1, res.messages.len());
+
+        let pokemon: Vec<Pokemon> = vec![Pokemon {
+            collection: Addr::unchecked("nft"),
+            token_id: 1,
+            species: String::from("charmander"),
+            index: 0,
+            health: MAX_HEALTH,
+            level: 1,
+            xp: 0,
+            original_trainer: String::from("hello.arch"),
+            variant: None,
+            genes: genes(&deps, 1),
+            moves: vec![],
+        }];
+
+        let player_data = Player {
+            id: String::from("hello.arch"),
+            address: Addr::unchecked("sender"),
+            potions: 0,
+            berries: 0,
+            default_pokemon: 0,
+            pokemons: pokemon,
+            wins: 0,
+            revives: 0,
+            tickets: 0,
+            last_center_visit: None,
+            last_forage: None,
+            streak: 0,
+            last_check_in: None,
+            badges: vec![],
+            encounter: None,
+        };
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetPlayer {
+                id: String::from("hello.arch"),
+            },
+        )
+        .unwrap();
+        let value: PlayerResponse = from_json(&res).unwrap();
+        assert_eq!(
+            PlayerResponse {
+                player: player_data
+            },
+            value
+        );
+    }
+
+    #[test]
+    fn test_game() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg { collection: None };
+        let info = mock_info("creator", &coins(1000, "earth"));
+
+        // we can just call .unwrap() to assert this was a success
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::SetNFTContract {
+            addr: "nft".to_string(),
+        };
+        let info = mock_info("creator", &[]);
+
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        add_species(&mut deps);
+
+        let msg = ExecuteMsg::Register {
+            id: String::from("hello.arch"),
+            starter: String::from("bulbasaur"),
+        };
+        let info = mock_info("sender", &[]);
+
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        encounter(&mut deps, "hello.arch", "pidgey");
+        let msg = ExecuteMsg::CatchPokemon {
+            id: String::from("hello.arch"),
+            species: String::from("pidgey"),
+            health: 32,
+            curr_pokemon: 0,
+        };
+        let info = mock_info("sender", &[]);
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(1, res.messages.len());
+
+        let pokemon: Vec<Pokemon> = vec![
+            Pokemon {
+                collection: Addr::unchecked("nft"),
+                token_id: 1,
+                species: String::from("bulbasaur"),
+                index: 0,
+                health: 32,
+                level: 1,
+                xp: 0,
+                original_trainer: String::from("hello.arch"),
+                variant: None,
+                genes: genes(&deps, 1),
+                moves: vec![],
+            },
+            Pokemon {
+                collection: Addr::unchecked("nft"),
+                token_id: 2,
+                species: String::from("pidgey"),
+                index: 1,
+                health: 100,
+                level: 1,
+                xp: 0,
+                original_trainer: String::from("hello.arch"),
+                variant: None,
+                genes: genes(&deps, 2),
+                moves: vec![],
+            },
+        ];
+
+        let player_data = Player {
+            id: String::from("hello.arch"),
+            address: Addr::unchecked("sender"),
+            potions: 0,
+            berries: 0,
+            default_pokemon: 0,
+            pokemons: pokemon,
+            wins: 0,
+            revives: 0,
+            tickets: 0,
+            last_center_visit: None,
+            last_forage: None,
+            streak: 0,
+            last_check_in: None,
+            badges: vec![],
+            encounter: None,
+        };
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetPlayer {
+                id: String::from("hello.arch"),
+            },
+        )
+        .unwrap();
+        let value: PlayerResponse = from_json(&res).unwrap();
+        assert_eq!(
+            PlayerResponse {
+                player: player_data
+            },
+            value
+        );
+
+        let msg = ExecuteMsg::VisitPokemonCenter {
+            id: String::from("hello.arch"),
+        };
+        let info = mock_info("sender", &[]);
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        let pokemon: Vec<Pokemon> = vec![
+            Pokemon {
+                collection: Addr::unchecked("nft"),
+                token_id: 1,
+                species: String::from("bulbasaur"),
+                index: 0,
+                health: 100,
+                level: 1,
+                xp: 0,
+                original_trainer: String::from("hello.arch"),
+                variant: None,
+                genes: genes(&deps, 1),
+                moves: vec![],
+            },
+            Pokemon {
+                collection: Addr::unchecked("nft"),
+                token_id: 2,
+                species: String::from("pidgey"),
+                index: 1,
+                health: 100,
+                level: 1,
+                xp: 0,
+                original_trainer: String::from("hello.arch"),
+                variant: None,
+                genes: genes(&deps, 2),
+                moves: vec![],
+            },
+        ];
+
+        let player_data = Player {
+            id: String::from("hello.arch"),
+            address: Addr::unchecked("sender"),
+            potions: 0,
+            berries: 0,
+            default_pokemon: 0,
+            pokemons: pokemon,
+            wins: 0,
+            revives: 0,
+            tickets: 0,
+            last_center_visit: Some(mock_env().block.time),
+            last_forage: None,
+            streak: 0,
+            last_check_in: None,
+            badges: vec![],
+            encounter: None,
+        };
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetPlayer {
+                id: String::from("hello.arch"),
+            },
+        )
+        .unwrap();
+        let value: PlayerResponse = from_json(&res).unwrap();
+        assert_eq!(
+            PlayerResponse {
+                player: player_data
+            },
+            value
+        );
+        encounter(&mut deps, "hello.arch", "pidgey");
+        let msg = ExecuteMsg::CatchPokemon {
+            id: String::from("hello.arch"),
+            species: String::from("pidgey"),
+            health: 32,
+            curr_pokemon: 0,
+        };
+        let info = mock_info("sender", &[]);
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(1, res.messages.len());
+    }
+
+    #[test]
+    fn leaderboard() {
+        let mut deps = mock_dependencies();
+
+        setup(&mut deps);
+
+        for id in ["ash.arch", "misty.arch"] {
+            let msg = ExecuteMsg::Register {
+                id: id.to_string(),
+                starter: "squirtle".to_string(),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
+        }
+        for (step, id) in ["misty.arch", "misty.arch", "ash.arch"].iter().enumerate() {
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(step as u64 * FORAGE_COOLDOWN);
+            let msg = ExecuteMsg::CollectBerries { id: id.to_string() };
+            execute(deps.as_mut(), env, mock_info("sender", &[]), msg).unwrap();
+        }
+        // nobody else can forage for a player and pump their score
+        let msg = ExecuteMsg::CollectBerries {
+            id: "ash.arch".to_string(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg);
+        assert!(matches!(err, Err(ContractError::NotPlayerOwner { .. })));
+
+        // only the owner reports battles
+        let msg = ExecuteMsg::RecordBattle {
+            winner: "ash.arch".to_string(),
+            loser: "misty.arch".to_string(),
+            pokemon: 0,
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            msg.clone(),
+        );
+        assert!(matches!(err, Err(ContractError::Unauthorized {})));
+        for _ in 0..4 {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("creator", &[]),
+                msg.clone(),
+            )
+            .unwrap();
+        }
+
+        let board = |deps: Deps<ArchwayQuery>, board, season, start_after: Option<&str>| {
+            let res = query(
+                deps,
+                mock_env(),
+                QueryMsg::Leaderboard {
+                    board,
+                    season,
+                    start_after: start_after.map(String::from),
+                    limit: Some(1),
+                },
+            )
+            .unwrap();
+            let value: LeaderboardResponse = from_json(&res).unwrap();
+            value
+        };
+        let entry = |player: &str, score| LeaderboardEntry {
+            player: player.to_string(),
+            score,
+        };
+
+        let res = board(deps.as_ref(), LeaderboardKind::Berries, None, None);
+        assert_eq!(vec![entry("misty.arch", 2)], res.entries);
+        let res = board(
+            deps.as_ref(),
+            LeaderboardKind::Berries,
+            None,
+            Some("misty.arch"),
+        );
+        assert_eq!(vec![entry("ash.arch", 1)], res.entries);
+        let res = board(deps.as_ref(), LeaderboardKind::Wins, None, None);
+        assert_eq!(vec![entry("ash.arch", 4)], res.entries);
+        let res = board(deps.as_ref(), LeaderboardKind::Level, None, None);
+        assert_eq!(vec![entry("ash.arch", 2)], res.entries);
+
+        let msg = ExecuteMsg::ResetSeason {};
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let res = board(deps.as_ref(), LeaderboardKind::Berries, None, None);
+        assert_eq!(1, res.season);
+        assert!(res.entries.is_empty());
+        let res = board(deps.as_ref(), LeaderboardKind::Berries, Some(0), None);
+        assert_eq!(vec![entry("misty.arch", 2)], res.entries);
+    }
+
+    #[test]
+    fn quests() {
+        let mut deps = mock_dependencies();
+        setup(&mut deps);
+        let msg = ExecuteMsg::Register {
+            id: String::from("hello.arch"),
+            starter: String::from("bulbasaur"),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
+
+        let set_quest = |id: &str, objective, reward| ExecuteMsg::SetQuest {
+            id: id.to_string(),
+            quest: Quest {
+                objective,
+                reward,
+                active: true,
+                guild: false,
+            },
+        };
+        let berries = set_quest(
+            "berries",
+            Objective::CollectBerries { count: 2 },
+            QuestReward {
+                potions: 2,
+                xp: 100,
+                pokemon: Some(String::from("pidgey")),
+                ..QuestReward::default()
+            },
+        );
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            berries.clone(),
+        );
+        assert!(matches!(err, Err(ContractError::Unauthorized {})));
+        let msg = set_quest(
+            "mew",
+            Objective::WinBattles { count: 1 },
+            QuestReward {
+                pokemon: Some(String::from("mew")),
+                ..QuestReward::default()
+            },
+        );
+        let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(err, Err(ContractError::UnknownSpecies { .. })));
+        // quests can't take anything from players, nor be done without doing anything
+        for (objective, reward) in [
+            (
+                Objective::WinBattles { count: 1 },
+                QuestReward {
+                    berries: -5,
+                    ..QuestReward::default()
+                },
+            ),
+            (Objective::WinBattles { count: 0 }, QuestReward::default()),
+        ] {
+            let msg = set_quest("broken", objective, reward);
+            let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+            assert!(matches!(err, Err(ContractError::InvalidAmount {})));
+        }
+        for (id, kind) in [("normal", "normal"), ("fire", "fire")] {
+            let msg = set_quest(
+                id,
+                Objective::Catch {
+                    count: 1,
+                    kind: Some(kind.to_string()),
+                },
+                QuestReward::default(),
+            );
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            berries,
+        )
+        .unwrap();
+
+        let claim = ExecuteMsg::ClaimQuest {
+            id: String::from("hello.arch"),
+            quest: String::from("berries"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            claim.clone(),
+        );
+        assert!(matches!(err, Err(ContractError::QuestIncomplete { .. })));
+
+        // progress is tracked by the regular handlers
+        encounter(&mut deps, "hello.arch", "pidgey");
+        let msg = ExecuteMsg::CatchPokemon {
+            id: String::from("hello.arch"),
+            species: String::from("pidgey"),
+            health: 100,
+            curr_pokemon: 0,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
+        for (step, completes) in [false, true].into_iter().enumerate() {
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(step as u64 * FORAGE_COOLDOWN);
+            let msg = ExecuteMsg::CollectBerries {
+                id: String::from("hello.arch"),
+            };
+            // berry quests can't be rushed by anyone, the player included
+            let err = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("anyone", &[]),
+                msg.clone(),
+            );
+            assert!(matches!(err, Err(ContractError::NotPlayerOwner { .. })));
+            let res = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("sender", &[]),
+                msg.clone(),
+            )
+            .unwrap();
+            let completed = res
+                .events
+                .iter()
+                .any(|event| event.ty == "pokearch.quest_completed");
+            assert_eq!(completes, completed);
+            let err = execute(deps.as_mut(), env, mock_info("sender", &[]), msg);
+            assert!(matches!(err, Err(ContractError::ForageCooldown { .. })));
+        }
+        let progress = |deps: Deps<ArchwayQuery>| {
+            let msg = QueryMsg::QuestProgress {
+                id: String::from("hello.arch"),
+            };
+            let res: Vec<QuestProgressEntry> =
+                from_json(query(deps, mock_env(), msg).unwrap()).unwrap();
+            res.into_iter()
+                .map(|entry| (entry.quest, entry.progress, entry.claimed))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec![
+                (String::from("berries"), 2, false),
+                (String::from("fire"), 0, false),
+                (String::from("normal"), 1, false),
+            ],
+            progress(deps.as_ref())
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            claim.clone(),
+        )
+        .unwrap();
+        assert_eq!(1, res.messages.len());
+        let player: PlayerResponse = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetPlayer {
+                    id: String::from("hello.arch"),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(2, player.player.potions);
+        assert_eq!(2, player.player.pokemons[0].level);
+        assert_eq!("pidgey", player.player.pokemons[2].species);
+        // the reward isn't a catch, only the wild pidgey counts
+        let msg = QueryMsg::Leaderboard {
+            board: LeaderboardKind::Catches,
+            season: None,
+            start_after: None,
+            limit: None,
+        };
+        let board: LeaderboardResponse =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(1, board.entries[0].score);
+        let err = execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), claim);
+        assert!(matches!(err, Err(ContractError::QuestClaimed { .. })));
+
+        // ended quests drop off unless there's still a reward to claim
+        for id in ["normal", "fire"] {
+            let msg = ExecuteMsg::SetQuest {
+                id: id.to_string(),
+                quest: Quest {
+                    objective: Objective::Catch {
+                        count: 1,
+                        kind: Some(id.to_string()),
+                    },
+                    reward: QuestReward::default(),
+                    active: false,
+                    guild: false,
+                },
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ActiveQuests {}).unwrap();
+        let active: Vec<QuestEntry> = from_json(res).unwrap();
+        assert_eq!(
+            vec!["berries"],
+            active.iter().map(|q| &q.id).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![
+                (String::from("berries"), 2, true),
+                (String::from("normal"), 1, false),
+            ],
+            progress(deps.as_ref())
+        );
+    }
+
+    #[test]
+    fn events() {
+        let mut deps = mock_dependencies();
+
+        setup(&mut deps);
+        spawn_only(&mut deps, "pidgey");
+
+        let mut run = |sender: &str, msg| {
+            let res = execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).unwrap();
+            assert_eq!(1, res.events.len());
+            res.events[0].clone()
+        };
+
+        let res = run("creator", ExecuteMsg::AddAllowance("sender".to_string()));
+        assert_eq!(
+            Event::new("pokearch.add_allowance")
+                .add_attribute("sender", "creator")
+                .add_attribute("address", "sender"),
+            res
+        );
+
+        let id = String::from("hello.arch");
+        let res = run(
+            "sender",
+            ExecuteMsg::Register {
+                id: id.clone(),
+                starter: String::from("bulbasaur"),
+            },
+        );
+        assert_eq!(
+            Event::new("pokearch.register")
+                .add_attribute("player", "hello.arch")
+                .add_attribute("sender", "sender")
+                .add_attribute("token_id", "1")
+                .add_attribute("species", "bulbasaur"),
+            res
+        );
+
+        let res = run(
+            "sender",
+            ExecuteMsg::Explore {
+                id: id.clone(),
+                region: String::from("pidgey"),
+            },
+        );
+        assert_eq!(
+            Event::new("pokearch.explore")
+                .add_attribute("player", "hello.arch")
+                .add_attribute("sender", "sender")
+                .add_attribute("region", "pidgey")
+                .add_attribute("species", "pidgey")
+                .add_attribute("level", "1")
+                .add_attribute("tickets_delta", "0"),
+            res
+        );
+
+        let res = run(
+            "sender",
+            ExecuteMsg::CatchPokemon {
+                id: id.clone(),
+                species: String::from("pidgey"),
+                health: 32,
+                curr_pokemon: 0,
+            },
+        );
+        assert_eq!(
+            Event::new("pokearch.catch")
+                .add_attribute("player", "hello.arch")
+                .add_attribute("sender", "sender")
+                .add_attribute("token_id", "2")
+                .add_attribute("species", "pidgey")
+                .add_attribute("level", "1")
+                .add_attribute("pokemon", "0")
+                .add_attribute("health_delta", "-68"),
+            res
+        );
+
+        let res = run("sender", ExecuteMsg::VisitPokemonCenter { id: id.clone() });
+        assert_eq!(
+            Event::new("pokearch.visit_pokemon_center")
+                .add_attribute("player", "hello.arch")
+                .add_attribute("sender", "sender")
+                .add_attribute("health_delta", "68")
+                .add_attribute("fee", "none"),
+            res
+        );
+
+        let res = run("sender", ExecuteMsg::CollectBerries { id: id.clone() });
+        assert_eq!(
+            Event::new("pokearch.collect_berries")
+                .add_attribute("player", "hello.arch")
+                .add_attribute("sender", "sender")
+                .add_attribute("berries_delta", "1"),
+            res
+        );
+
+        let res = run("sender", ExecuteMsg::SetDefaultPokemon { id, pokemon: 1 });
+        assert_eq!(
+            Event::new("pokearch.set_default_pokemon")
+                .add_attribute("player", "hello.arch")
+                .add_attribute("sender", "sender")
+                .add_attribute("pokemon", "1"),
+            res
+        );
+
+        let res = run("creator", ExecuteMsg::RemoveAllowance("sender".to_string()));
+        assert_eq!(
+            Event::new("pokearch.remove_allowance")
+                .add_attribute("sender", "creator")
+                .add_attribute("address", "sender"),
+            res
+        );
+    }
+
+    #[test]
+    fn roster_validation() {
+        let mut deps = mock_dependencies();
+
+        setup(&mut deps);
+        let id = String::from("hello.arch");
+        let msg = ExecuteMsg::Register {
+            id: id.clone(),
+            starter: String::from("bulbasaur"),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::SetDefaultPokemon {
+            id: id.clone(),
+            pokemon: 1,
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg);
+        assert!(matches!(
+            err,
+            Err(ContractError::PokemonNotFound { index: 1 })
+        ));
+
+        let msg = ExecuteMsg::SetDefaultPokemon {
+            id: id.clone(),
+            pokemon: 0,
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("thief", &[]), msg);
+        assert!(matches!(err, Err(ContractError::NotPlayerOwner { .. })));
+
+        let msg = ExecuteMsg::Revive {
+            id: id.clone(),
+            pokemon: -1,
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg);
+        assert!(matches!(
+            err,
+            Err(ContractError::PokemonNotFound { index: -1 })
+        ));
+
+        // the starter faints while catching and can no longer be used to catch
+        encounter(&mut deps, "hello.arch", "pidgey");
+        let msg = ExecuteMsg::CatchPokemon {
+            id: id.clone(),
+            species: String::from("pidgey"),
+            health: -20,
+            curr_pokemon: 0,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        let err = execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg);
+        assert!(matches!(
+            err,
+            Err(ContractError::PokemonFainted { index: 0 })
+        ));
+    }
+
+    #[test]
+    fn pokemon_center() {
+        let mut deps = mock_dependencies();
+
+        setup(&mut deps);
+        let msg = ExecuteMsg::SetPokemonCenter {
+            cooldown: 600,
+            fee: Some(coin(5, "aarch")),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let id = String::from("hello.arch");
+        let msg = ExecuteMsg::Register {
+            id: id.clone(),
+            starter: String::from("bulbasaur"),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
+        encounter(&mut deps, "hello.arch", "pidgey");
+        let msg = ExecuteMsg::CatchPokemon {
+            id: id.clone(),
+            species: String::from("pidgey"),
+            health: 0,
+            curr_pokemon: 0,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
+
+        let revive = ExecuteMsg::Revive {
+            id: id.clone(),
+            pokemon: 0,
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            revive.clone(),
+        );
+        assert!(matches!(err, Err(ContractError::NoRevives {})));
+        PLAYERS
+            .update(
+                deps.as_mut().storage,
+                id.clone(),
+                |player| -> StdResult<_> {
+                    let mut player = player.unwrap();
+                    player.revives = 1;
+                    Ok(player)
+                },
+            )
+            .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            revive.clone(),
+        )
+        .unwrap();
+        let err = execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), revive);
+        assert!(matches!(
+            err,
+            Err(ContractError::PokemonNotFainted { index: 0 })
+        ));
+
+        // the first visit is free, the next one costs the fee until the cooldown is over
+        let visit = ExecuteMsg::VisitPokemonCenter { id: id.clone() };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            visit.clone(),
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            visit.clone(),
+        );
+        assert!(matches!(
+            err,
+            Err(ContractError::PokemonCenterCooldown { .. })
+        ));
+        // anything but the exact fee is refused rather than kept
+        for funds in [
+            coins(6, "aarch"),
+            coins(5, "uarch"),
+            vec![coin(5, "aarch"), coin(1, "uarch")],
+        ] {
+            let info = mock_info("sender", &funds);
+            let err = execute(deps.as_mut(), mock_env(), info, visit.clone());
+            assert!(matches!(err, Err(ContractError::WrongFee { .. })));
+        }
+        let info = mock_info("sender", &coins(5, "aarch"));
+        let res = execute(deps.as_mut(), mock_env(), info, visit.clone()).unwrap();
+        assert_eq!(Attribute::new("fee", "5aarch"), res.events[0].attributes[3]);
+
+        // free visits take no funds
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(600);
+        let info = mock_info("sender", &coins(5, "aarch"));
+        let err = execute(deps.as_mut(), env.clone(), info, visit.clone());
+        assert!(matches!(err, Err(ContractError::UnexpectedFunds {})));
+        execute(deps.as_mut(), env, mock_info("sender", &[]), visit).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetPlayer { id }).unwrap();
+        let value: PlayerResponse = from_json(&res).unwrap();
+        assert_eq!(0, value.player.revives);
+        assert!(value.player.pokemons.iter().all(|p| p.health == MAX_HEALTH));
+    }
+
+    #[test]
+    fn gyms() {
+        let mut deps = mock_dependencies();
+        setup(&mut deps);
+        let id = String::from("hello.arch");
+        let msg = ExecuteMsg::Register {
+            id: id.clone(),
+            starter: String::from("bulbasaur"),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
+
+        let gym =
+            |species: &str, level, min_level, required_badge: Option<&str>, badge: &str| Gym {
+                leader: String::from("brock"),
+                team: vec![GymPokemon {
+                    species: species.to_string(),
+                    level,
+                }],
+                min_level,
+                required_badge: required_badge.map(String::from),
+                badge: badge.to_string(),
+            };
+        let msg = ExecuteMsg::SetGym {
+            id: String::from("pewter"),
+            gym: gym("squirtle", 1, 1, None, "boulder"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            msg.clone(),
+        );
+        assert!(matches!(err, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::SetGym {
+            id: String::from("cerulean"),
+            gym: gym("mew", 1, 1, None, "cascade"),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(err, Err(ContractError::UnknownSpecies { .. })));
+        for (gym_id, gym) in [
+            (
+                "cerulean",
+                gym("charmander", 30, 1, Some("boulder"), "cascade"),
+            ),
+            ("plateau", gym("pidgey", 1, 5, None, "elite")),
+        ] {
+            let msg = ExecuteMsg::SetGym {
+                id: gym_id.to_string(),
+                gym,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+        let msg = ExecuteMsg::SetSpecies {
+            species: Species {
+                name: String::from("onix"),
+                types: vec![String::from("rock")],
+                image: None,
+                collection: None,
+                base_stats: Stats {
+                    attack: 50,
+                    defense: 50,
+                    speed: 50,
+                },
+                required_badge: Some(String::from("boulder")),
+                supply: None,
+                variant_odds: None,
+                learnset: vec![],
+            },
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let challenge = |gym: &str, party: Vec<i32>| ExecuteMsg::ChallengeGym {
+            id: id.clone(),
+            gym: gym.to_string(),
+            party,
+        };
+        // onix doesn't spawn for players without the badge, nor can they catch it
+        spawn_only(&mut deps, "onix");
+        let explore = ExecuteMsg::Explore {
+            id: id.clone(),
+            region: String::from("onix"),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), explore);
+        assert!(matches!(err, Err(ContractError::NothingSpawns { .. })));
+        let catch = ExecuteMsg::CatchPokemon {
+            id: id.clone(),
+            species: String::from("onix"),
+            health: 100,
+            curr_pokemon: 0,
+        };
+        let info = mock_info("sender", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), catch.clone());
+        assert!(matches!(err, Err(ContractError::MissingBadge { .. })));
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            challenge("cerulean", vec![0]),
+        );
+        assert!(matches!(err, Err(ContractError::MissingBadge { .. })));
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            challenge("plateau", vec![0]),
+        );
+        assert!(matches!(
+            err,
+            Err(ContractError::LevelTooLow { required: 5, .. })
+        ));
+        for party in [vec![], vec![0, 0]] {
+            let err = execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                challenge("pewter", party),
+            );
+            assert!(matches!(err, Err(ContractError::InvalidParty { .. })));
+        }
+
+        // the challenger strikes first and, with its genes, wins with damage taken
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            challenge("pewter", vec![0]),
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                Attribute::new("result", "won"),
+                Attribute::new("health_delta", "-56"),
+                Attribute::new("badge", "boulder"),
+            ],
+            res.events[0].attributes[4..].to_vec()
+        );
+        encounter(&mut deps, "hello.arch", "onix");
+        execute(deps.as_mut(), mock_env(), info.clone(), catch).unwrap();
+
+        // a much stronger leader knocks the party out
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            challenge("cerulean", vec![0]),
+        )
+        .unwrap();
+        assert_eq!(
+            Attribute::new("result", "lost"),
+            res.events[0].attributes[4]
+        );
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetPlayer { id }).unwrap();
+        let player: PlayerResponse = from_json(res).unwrap();
+        assert_eq!(vec![String::from("boulder")], player.player.badges);
+        assert!(player.player.pokemons[0].is_fainted());
+    }
+
+    #[test]
+    fn check_in() {
+        let mut deps = mock_dependencies();
+        setup(&mut deps);
+        let id = String::from("hello.arch");
+        let msg = ExecuteMsg::Register {
+            id: id.clone(),
+            starter: String::from("bulbasaur"),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
+
+        let day = |day: u64| {
+            let mut env = mock_env();
+            env.block.time = Timestamp::from_seconds(day * SECONDS_PER_DAY + 3600);
+            env
+        };
+        let check_in = |protect_streak| ExecuteMsg::CheckIn {
+            id: id.clone(),
+            protect_streak,
+        };
+        let player = |deps: Deps<ArchwayQuery>| {
+            let msg = QueryMsg::GetPlayer {
+                id: String::from("hello.arch"),
+            };
+            let res: PlayerResponse = from_json(query(deps, mock_env(), msg).unwrap()).unwrap();
+            res.player
+        };
+
+        let info = mock_info("sender", &[]);
+        execute(deps.as_mut(), day(10), info.clone(), check_in(false)).unwrap();
+        let err = execute(deps.as_mut(), day(10), info.clone(), check_in(false));
+        assert!(matches!(
+            err,
+            Err(ContractError::AlreadyCheckedIn { next }) if next == 11 * SECONDS_PER_DAY
+        ));
+        execute(deps.as_mut(), day(11), info.clone(), check_in(false)).unwrap();
+        let res = player(deps.as_ref());
+        assert_eq!((2, 3), (res.streak, res.berries));
+        assert_eq!(Some(day(11).block.time), res.last_check_in);
+
+        // protecting the streak over a missed day costs five berries
+        let err = execute(deps.as_mut(), day(13), info.clone(), check_in(true));
+        assert!(matches!(err, Err(ContractError::StreakNotProtected {})));
+        execute(deps.as_mut(), day(13), info.clone(), check_in(false)).unwrap();
+        let res = player(deps.as_ref());
+        assert_eq!((1, 4), (res.streak, res.berries));
+
+        let items = |kind, amount| Items { kind, amount };
+        let msg = ExecuteMsg::SetCheckInSchedule {
+            schedule: CheckInSchedule {
+                rewards: vec![items(ItemKind::Berry, 2), items(ItemKind::Ticket, 1)],
+                protection: Some(items(ItemKind::Berry, 1)),
+            },
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone());
+        assert!(matches!(err, Err(ContractError::Unauthorized {})));
+        // rewards and protection can't take items away
+        for (reward, protection) in [(0, 1), (2, -1)] {
+            let msg = ExecuteMsg::SetCheckInSchedule {
+                schedule: CheckInSchedule {
+                    rewards: vec![items(ItemKind::Berry, reward)],
+                    protection: Some(items(ItemKind::Berry, protection)),
+                },
+            };
+            let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+            assert!(matches!(err, Err(ContractError::InvalidAmount {})));
+        }
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // two missed days cost two berries, and the last reward repeats once the schedule ends
+        execute(deps.as_mut(), day(16), info.clone(), check_in(true)).unwrap();
+        execute(deps.as_mut(), day(17), info, check_in(false)).unwrap();
+        let res = player(deps.as_ref());
+        assert_eq!((3, 2, 2), (res.streak, res.berries, res.tickets));
+    }
+
+    #[test]
+    fn starter_choice() {
+        let mut deps = mock_dependencies();
+        setup(&mut deps);
+
+        let msg = ExecuteMsg::Register {
+            id: String::from("hello.arch"),
+            starter: String::from("pidgey"),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg);
+        assert!(matches!(err, Err(ContractError::InvalidStarter { .. })));
+
+        let msg = ExecuteMsg::SetStarters {
+            starters: vec![String::from("mew")],
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(err, Err(ContractError::UnknownSpecies { .. })));
+
+        let msg = ExecuteMsg::SetMetadataRenderer {
+            base_uri: String::from("https://render.pokearch.io/"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            msg.clone(),
+        );
+        assert!(matches!(err, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // the starter is minted to the player, not to the contract
+        let msg = ExecuteMsg::Register {
+            id: String::from("hello.arch"),
+            starter: String::from("squirtle"),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
+        // pointing at the renderer rather than a snapshot of the metadata
+        let mint = Cw721ExecuteMsg::Mint {
+            token_id: String::from("1"),
+            owner: String::from("sender"),
+            token_uri: Some(String::from("https://render.pokearch.io/nft/1")),
+            extension: TokenExtension {
+                publisher: "PokeArch".to_string(),
+            },
+        };
+        assert_eq!(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("nft"),
+                msg: to_json_binary(&mint).unwrap(),
+                funds: vec![],
+            }),
+            res.messages[0].msg
+        );
+    }
+
+    #[test]
+    fn rewards() {
+        let mut deps = mock_dependencies();
+        setup(&mut deps);
+        let msg = ExecuteMsg::AddAllowance("sender".to_string());
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::PendingRewards {}).unwrap();
+        let pending: PendingRewardsResponse = from_json(res).unwrap();
+        assert_eq!(2, pending.records);
+        assert_eq!(coins(1000, "aarch"), pending.rewards);
+
+        for (gas_tank, prize_pool) in [
+            (Decimal::percent(60), Decimal::percent(50)),
+            (Decimal::MAX, Decimal::MAX),
+        ] {
+            let msg = ExecuteMsg::SetRewardsSplit {
+                gas_tank,
+                prize_pool,
+                treasury: "treasury".to_string(),
+            };
+            let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+            assert!(matches!(err, Err(ContractError::InvalidRewardsSplit {})));
+        }
+
+        let msg = ExecuteMsg::SetRewardsSplit {
+            gas_tank: Decimal::percent(50),
+            prize_pool: Decimal::percent(25),
+            treasury: "treasury".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::WithdrawRewards {};
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            msg.clone(),
+        );
+        assert!(matches!(err, Err(ContractError::Unauthorized {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(WITHDRAW_REWARDS_REPLY_ID, res.messages[0].id);
+
+        // the chain answers with what was withdrawn, which gets split three ways
+        let withdrawn = WithdrawRewardsResponse {
+            records_num: 2,
+            total_rewards: coins(1001, "aarch"),
+        };
+        let msg = Reply {
+            id: WITHDRAW_REWARDS_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(to_json_binary(&withdrawn).unwrap()),
+            }),
+        };
+        let res = reply(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: coins(251, "aarch"),
+            }),
+            res.messages[0].msg
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Rewards {}).unwrap();
+        let rewards: RewardsResponse = from_json(res).unwrap();
+        assert_eq!(coins(500, "aarch"), rewards.gas_tank);
+        assert_eq!(coins(250, "aarch"), rewards.prize_pool);
+        assert_eq!(coins(1001, "aarch"), rewards.ledger.withdrawn);
+        assert_eq!(coins(251, "aarch"), rewards.ledger.treasury);
+
+        // fee grants are paid from the gas tank until it runs dry
+        let grant = |amount| {
+            SudoMsg::CwGrant(CwGrant {
+                fee_requested: coins(amount, "aarch"),
+                msgs: vec![crate::cwfees::Msg {
+                    sender: "sender".to_string(),
+                    type_url: "cosmwasm.wasm.v1.MsgExecuteContract".to_string(),
+                    msg: Binary::default(),
+                }],
+            })
+        };
+        sudo(deps.as_mut(), mock_env(), grant(400)).unwrap();
+        let err = sudo(deps.as_mut(), mock_env(), grant(101));
+        assert!(matches!(err, Err(ContractError::GasTankEmpty {})));
+        let msg = ExecuteMsg::FundGasTank {};
+        let funds = coins(1, "aarch");
+        execute(deps.as_mut(), mock_env(), mock_info("anyone", &funds), msg).unwrap();
+        sudo(deps.as_mut(), mock_env(), grant(101)).unwrap();
+
+        // prizes come out of the prize pool
+        let msg = ExecuteMsg::Register {
+            id: String::from("hello.arch"),
+            starter: String::from("bulbasaur"),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
+        let award = |amount| ExecuteMsg::AwardPrize {
+            id: String::from("hello.arch"),
+            amount: coin(amount, "aarch"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            award(251),
+        );
+        assert!(matches!(err, Err(ContractError::PrizePoolEmpty { .. })));
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            award(250),
+        )
+        .unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "sender".to_string(),
+                amount: coins(250, "aarch"),
+            }),
+            res.messages[0].msg
+        );
     }
 
     #[test]
-    fn set_nft_address() {
+    fn rewards_paid_elsewhere() {
+        let mut deps = mock_dependencies_paying("treasury");
+        setup(&mut deps);
+
+        // nothing reaches the contract, so nothing is split or credited
+        let withdrawn = WithdrawRewardsResponse {
+            records_num: 2,
+            total_rewards: coins(1000, "aarch"),
+        };
+        let msg = Reply {
+            id: WITHDRAW_REWARDS_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(to_json_binary(&withdrawn).unwrap()),
+            }),
+        };
+        let res = reply(deps.as_mut(), mock_env(), msg).unwrap();
+        assert!(res.messages.is_empty());
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Rewards {}).unwrap();
+        let rewards: RewardsResponse = from_json(res).unwrap();
+        assert!(rewards.gas_tank.is_empty());
+        assert_eq!(RewardsLedger::default(), rewards.ledger);
+    }
+
+    #[test]
+    fn premium() {
         let mut deps = mock_dependencies();
-        let msg = InstantiateMsg {};
-        let info = mock_info("creator", &coins(1000, "earth"));
+        setup(&mut deps);
+        let msg = ExecuteMsg::AddAllowance("sender".to_string());
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::FundGasTank {};
+        let funds = coins(1000, "aarch");
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &funds), msg).unwrap();
 
-        // we can just call .unwrap() to assert this was a success
-        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let msg = ExecuteMsg::SetPremium {
+            flat_fee: coin(50, "aarch"),
+            grant_policy: GrantPolicy::Exclude,
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            msg.clone(),
+        );
+        assert!(matches!(err, Err(ContractError::Unauthorized {})));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(
+            CosmosMsg::Custom(ArchwayMsg::set_flat_fee(
+                mock_env().contract.address,
+                coin(50, "aarch")
+            )),
+            res.messages[0].msg
+        );
 
-        let msg = ExecuteMsg::SetNFTContract {
-            addr: Addr::unchecked("nft").to_string(),
-            token_uri: String::from("hello"),
+        let grant_raw = |msg: Vec<u8>| {
+            let execute = MsgExecuteContract {
+                sender: "sender".to_string(),
+                contract: mock_env().contract.address.to_string(),
+                msg,
+            };
+            SudoMsg::CwGrant(CwGrant {
+                fee_requested: coins(100, "aarch"),
+                msgs: vec![crate::cwfees::Msg {
+                    sender: "sender".to_string(),
+                    type_url: "cosmwasm.wasm.v1.MsgExecuteContract".to_string(),
+                    msg: Binary::from(prost::Message::encode_to_vec(&execute)),
+                }],
+            })
         };
-        let info = mock_info("creator", &[]);
+        let grant = |msg: &ExecuteMsg| grant_raw(to_json_binary(msg).unwrap().to_vec());
+        let catch = ExecuteMsg::CatchPokemon {
+            id: String::from("hello.arch"),
+            species: String::from("pidgey"),
+            health: 50,
+            curr_pokemon: 0,
+        };
+        let err = sudo(deps.as_mut(), mock_env(), grant(&catch));
+        assert!(matches!(err, Err(ContractError::PremiumNotGranted {})));
+        let breed = ExecuteMsg::Breed {
+            id: String::from("hello.arch"),
+            first: 0,
+            second: 1,
+        };
+        let err = sudo(deps.as_mut(), mock_env(), grant(&breed));
+        assert!(matches!(err, Err(ContractError::PremiumNotGranted {})));
+        let berries = ExecuteMsg::CollectBerries {
+            id: String::from("hello.arch"),
+        };
+        // everyday actions are still sponsored, flat fee and all
+        let tank = |deps: &MockDeps| {
+            GAS_TANK
+                .load(&deps.storage)
+                .unwrap()
+                .into_iter()
+                .find(|coin| coin.denom == "aarch")
+                .unwrap()
+                .amount
+                .u128()
+        };
+        let before = tank(&deps);
+        sudo(deps.as_mut(), mock_env(), grant(&berries)).unwrap();
+        assert_eq!(before - 100, tank(&deps));
+        // messages that don't parse are left to fail on execution
+        sudo(deps.as_mut(), mock_env(), grant_raw(b"{}".to_vec())).unwrap();
 
-        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(1, res.messages.len());
+        // sponsoring covers premium actions as well
+        let msg = ExecuteMsg::SetPremium {
+            flat_fee: coin(50, "aarch"),
+            grant_policy: GrantPolicy::Sponsor,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        sudo(deps.as_mut(), mock_env(), grant(&catch)).unwrap();
+
+        // and without a flat fee there is no premium to exclude
+        let msg = ExecuteMsg::SetPremium {
+            flat_fee: coin(0, "aarch"),
+            grant_policy: GrantPolicy::Exclude,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        sudo(deps.as_mut(), mock_env(), grant(&catch)).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Premium {}).unwrap();
+        let premium: Premium = from_json(res).unwrap();
+        assert_eq!(None, premium.flat_fee);
     }
 
     #[test]
-    fn add_allowance() {
+    fn pause() {
         let mut deps = mock_dependencies();
-        let msg = ExecuteMsg::AddAllowance(Addr::unchecked("sender").to_string());
+        setup(&mut deps);
+        let msg = ExecuteMsg::Register {
+            id: String::from("hello.arch"),
+            starter: String::from("bulbasaur"),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
+        encounter(&mut deps, "hello.arch", "pidgey");
+        let catch = ExecuteMsg::CatchPokemon {
+            id: String::from("hello.arch"),
+            species: String::from("pidgey"),
+            health: 50,
+            curr_pokemon: 0,
+        };
+        let berries = ExecuteMsg::CollectBerries {
+            id: String::from("hello.arch"),
+        };
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("guardian", &[]),
+            ExecuteMsg::Pause {},
+        );
+        assert!(matches!(err, Err(ContractError::Unauthorized {})));
+        let msg = ExecuteMsg::AddGuardian("guardian".to_string());
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("guardian", &[]),
+            msg.clone(),
+        );
+        assert!(matches!(err, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("guardian", &[]),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            berries.clone(),
+        );
+        assert!(matches!(err, Err(ContractError::Paused { feature }) if feature == "all"));
+        // the owner can still fix things while the game is paused
+        let msg = ExecuteMsg::SetStarters {
+            starters: vec![String::from("pidgey")],
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("guardian", &[]),
+            ExecuteMsg::Unpause {},
+        )
+        .unwrap();
+
+        let msg = ExecuteMsg::SetFeature {
+            feature: Feature::Catching,
+            enabled: false,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), msg).unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            catch.clone(),
+        );
+        assert!(matches!(err, Err(ContractError::Paused { feature }) if feature == "catching"));
+        // quest rewards are another way to mint pokemon
+        let msg = ExecuteMsg::ClaimQuest {
+            id: String::from("hello.arch"),
+            quest: String::from("any"),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg);
+        assert!(matches!(err, Err(ContractError::Paused { feature }) if feature == "catching"));
+        execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), berries).unwrap();
+
+        let msg = ExecuteMsg::SetFeature {
+            feature: Feature::FeeGrants,
+            enabled: false,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), msg).unwrap();
+        let grant = SudoMsg::CwGrant(CwGrant {
+            fee_requested: vec![],
+            msgs: vec![],
+        });
+        let err = sudo(deps.as_mut(), mock_env(), grant);
+        assert!(matches!(err, Err(ContractError::Paused { feature }) if feature == "fee_grants"));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::PauseStatus {}).unwrap();
+        let status: PauseStatusResponse = from_json(res).unwrap();
+        assert!(!status.paused);
+        assert_eq!(vec![Feature::Catching, Feature::FeeGrants], status.disabled);
+
+        let msg = ExecuteMsg::SetFeature {
+            feature: Feature::Catching,
+            enabled: true,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), msg).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), catch).unwrap();
+    }
+
+    #[test]
+    fn token_ids_exhausted() {
+        let mut deps = mock_dependencies_with_tokens(i32::MAX as u64);
+        setup(&mut deps);
+        let msg = ExecuteMsg::Register {
+            id: String::from("hello.arch"),
+            starter: String::from("bulbasaur"),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg);
+        assert!(matches!(err, Err(ContractError::Std(_))));
+    }
+
+    #[test]
+    fn mint_replies() {
+        let mut deps = mock_dependencies_with_tokens(5);
+        let info = mock_info("creator", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InstantiateMsg { collection: None },
+        )
+        .unwrap();
+        // the collection is already in use, so ids continue after its tokens
+        let msg = ExecuteMsg::SetNFTContract {
+            addr: "nft".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::Register {
+            id: String::from("hello.arch"),
+            starter: String::from("bulbasaur"),
+        };
         let info = mock_info("sender", &[]);
+        // nobody can register before the starters are picked
+        let err = super::execute(deps.as_mut(), mock_env(), info.clone(), msg.clone());
+        assert!(matches!(err, Err(ContractError::NoStarters {})));
+        add_species(&mut deps);
+        let res = super::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(MINT_REPLY_ID, res.messages[0].id);
+        assert!(res.events[0]
+            .attributes
+            .contains(&Attribute::new("token_id", "6")));
 
-        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
+        // nothing joins the roster until the mint is confirmed
+        let player = query::get_player(deps.as_ref(), String::from("hello.arch")).unwrap();
+        assert!(player.player.pokemons.is_empty());
 
-        let res = query(
-            deps.as_ref(),
+        let result = SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        });
+        let msg = Reply {
+            id: MINT_REPLY_ID,
+            result,
+        };
+        reply(deps.as_mut(), mock_env(), msg).unwrap();
+        let player = query::get_player(deps.as_ref(), String::from("hello.arch")).unwrap();
+        assert_eq!(6, player.player.pokemons[0].token_id);
+        let tokens = TOKENS.load(&deps.storage, Addr::unchecked("nft"));
+        assert_eq!(6, tokens.unwrap());
+    }
+
+    #[test]
+    fn collections() {
+        let mut deps = mock_dependencies();
+        setup(&mut deps);
+
+        let species = Species {
+            name: String::from("chikorita"),
+            types: vec![String::from("grass")],
+            image: Some(String::from("ipfs://chikorita")),
+            collection: Some(String::from("gen2")),
+            base_stats: Stats {
+                attack: 50,
+                defense: 50,
+                speed: 50,
+            },
+            required_badge: None,
+            supply: None,
+            variant_odds: None,
+            learnset: vec![],
+        };
+        let msg = ExecuteMsg::SetSpecies {
+            species: species.clone(),
+        };
+        let err = execute(
+            deps.as_mut(),
             mock_env(),
-            QueryMsg::CheckAllowance {
-                addr: Addr::unchecked("sender").to_string(),
+            mock_info("creator", &[]),
+            msg.clone(),
+        );
+        assert!(matches!(err, Err(ContractError::UnknownCollection { .. })));
+
+        let set_collection = ExecuteMsg::SetCollection {
+            id: String::from("gen2"),
+            addr: String::from("nft2"),
+        };
+        let info = mock_info("sender", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, set_collection.clone());
+        assert!(matches!(err, Err(ContractError::Unauthorized {})));
+        let info = mock_info("creator", &[]);
+        execute(deps.as_mut(), mock_env(), info, set_collection).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::Register {
+            id: String::from("hello.arch"),
+            starter: String::from("bulbasaur"),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
+        encounter(&mut deps, "hello.arch", "chikorita");
+        let msg = ExecuteMsg::CatchPokemon {
+            id: String::from("hello.arch"),
+            species: String::from("chikorita"),
+            health: 80,
+            curr_pokemon: 0,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
+        let CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) = &res.messages[0].msg else {
+            panic!("expected a mint");
+        };
+        assert_eq!("nft2", contract_addr);
+
+        // ids are counted per collection
+        let player = query::get_player(deps.as_ref(), String::from("hello.arch")).unwrap();
+        assert_eq!(
+            Addr::unchecked("nft2"),
+            player.player.pokemons[1].collection
+        );
+        assert_eq!(1, player.player.pokemons[1].token_id);
+    }
+
+    #[test]
+    fn pokemon_metadata() {
+        let mut deps = mock_dependencies();
+        setup(&mut deps);
+        for (id, sender) in [("hello.arch", "sender"), ("rival.arch", "rival")] {
+            let msg = ExecuteMsg::Register {
+                id: id.to_string(),
+                starter: String::from("bulbasaur"),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).unwrap();
+        }
+
+        let msg = QueryMsg::PokemonMetadata {
+            collection: String::from("nft"),
+            token_id: 1,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg.clone()).unwrap();
+        let metadata: PokemonMetadata = from_json(res).unwrap();
+        let base = Stats {
+            attack: 50,
+            defense: 50,
+            speed: 50,
+        };
+        let genes = genes(&deps, 1);
+        assert_eq!(
+            PokemonMetadata {
+                species: String::from("bulbasaur"),
+                types: vec![String::from("normal")],
+                level: 1,
+                xp: 0,
+                stats: genes.apply(&base, 1),
+                original_trainer: String::from("hello.arch"),
+                variant: None,
+                trainer: String::from("hello.arch"),
+            },
+            metadata
+        );
+
+        // levelling up changes the metadata and tells indexers about it
+        let battle = ExecuteMsg::RecordBattle {
+            winner: String::from("hello.arch"),
+            loser: String::from("rival.arch"),
+            pokemon: 0,
+        };
+        for wins in 1..=4 {
+            let info = mock_info("creator", &[]);
+            let res = execute(deps.as_mut(), mock_env(), info, battle.clone()).unwrap();
+            let updated = res
+                .events
+                .iter()
+                .any(|event| event.ty == "pokearch.metadata_update");
+            assert_eq!(wins == 4, updated);
+        }
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let metadata: PokemonMetadata = from_json(res).unwrap();
+        assert_eq!(2, metadata.level);
+        assert_eq!(genes.apply(&base, 2), metadata.stats);
+    }
+
+    #[test]
+    fn token_metadata() {
+        let mut deps = mock_dependencies();
+        setup(&mut deps);
+        let msg = ExecuteMsg::Register {
+            id: String::from("<b>hello.arch</b>"),
+            starter: String::from("bulbasaur"),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
+
+        let msg = QueryMsg::TokenMetadata {
+            collection: None,
+            token_id: 1,
+            data_uri: Some(true),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let res: TokenMetadataResponse = from_json(res).unwrap();
+        assert_eq!("Bulbasaur #1", res.metadata.name);
+        let trait_value = |trait_type: &str| {
+            res.metadata
+                .attributes
+                .iter()
+                .find(|attribute| attribute.trait_type == trait_type)
+                .map(|attribute| attribute.value.clone())
+        };
+        assert_eq!(Some(String::from("normal")), trait_value("Type"));
+        assert_eq!(Some(String::from("1")), trait_value("Level"));
+        let genes = genes(&deps, 1);
+        let stats = Stats {
+            attack: 50,
+            defense: 50,
+            speed: 50,
+        };
+        let speed = genes.apply(&stats, 1).speed;
+        assert_eq!(Some(speed.to_string()), trait_value("Speed"));
+        assert_eq!(
+            Some(genes.nature.as_str().to_string()),
+            trait_value("Nature")
+        );
+
+        // the card embeds the species artwork and escapes player input
+        let svg = res
+            .metadata
+            .image
+            .strip_prefix("data:image/svg+xml;base64,")
+            .unwrap();
+        let svg = String::from_utf8(Binary::from_base64(svg).unwrap().0).unwrap();
+        assert!(svg.contains(r#"<image href="ipfs://bulbasaur""#));
+        assert!(svg.contains("OT &lt;b&gt;hello.arch&lt;/b&gt;"));
+
+        let token_uri = res.token_uri.unwrap();
+        let json = token_uri
+            .strip_prefix("data:application/json;base64,")
+            .unwrap();
+        let decoded: TokenMetadata = from_json(Binary::from_base64(json).unwrap()).unwrap();
+        assert_eq!(res.metadata, decoded);
+
+        let msg = QueryMsg::TokenMetadata {
+            collection: Some(String::from("nft")),
+            token_id: 1,
+            data_uri: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let res: TokenMetadataResponse = from_json(res).unwrap();
+        assert_eq!(None, res.token_uri);
+    }
+
+    #[test]
+    fn guilds() {
+        let mut deps = mock_dependencies();
+        setup(&mut deps);
+        for id in ["ash.arch", "misty.arch", "brock.arch"] {
+            let msg = ExecuteMsg::Register {
+                id: id.to_string(),
+                starter: String::from("squirtle"),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
+        }
+        let run = |deps: &mut MockDeps, msg: ExecuteMsg, funds: &[Coin]| {
+            execute(deps.as_mut(), mock_env(), mock_info("sender", funds), msg)
+        };
+        let create = |max_members| ExecuteMsg::CreateGuild {
+            id: String::from("ash.arch"),
+            guild: String::from("rockets"),
+            name: String::from("Team Rocket"),
+            max_members,
+        };
+        let err = run(&mut deps, create(0), &[]);
+        assert!(matches!(err, Err(ContractError::InvalidGuildSize { .. })));
+        run(&mut deps, create(2), &[]).unwrap();
+
+        let join = |id: &str| ExecuteMsg::JoinGuild {
+            id: id.to_string(),
+            guild: String::from("rockets"),
+        };
+        let err = run(&mut deps, join("misty.arch"), &[]);
+        assert!(matches!(err, Err(ContractError::NotInvited { .. })));
+        for player in ["misty.arch", "brock.arch"] {
+            let msg = ExecuteMsg::InviteToGuild {
+                id: String::from("ash.arch"),
+                player: player.to_string(),
+            };
+            run(&mut deps, msg, &[]).unwrap();
+        }
+        run(&mut deps, join("misty.arch"), &[]).unwrap();
+        let err = run(&mut deps, join("brock.arch"), &[]);
+        assert!(matches!(err, Err(ContractError::GuildFull { .. })));
+
+        // members can't invite until promoted
+        let msg = ExecuteMsg::InviteToGuild {
+            id: String::from("misty.arch"),
+            player: String::from("brock.arch"),
+        };
+        let err = run(&mut deps, msg, &[]);
+        assert!(matches!(err, Err(ContractError::Unauthorized {})));
+        let msg = ExecuteMsg::SetGuildRole {
+            id: String::from("ash.arch"),
+            player: String::from("misty.arch"),
+            role: GuildRole::Officer,
+        };
+        run(&mut deps, msg, &[]).unwrap();
+        let leave = |id: &str| ExecuteMsg::LeaveGuild { id: id.to_string() };
+        let err = run(&mut deps, leave("ash.arch"), &[]);
+        assert!(matches!(err, Err(ContractError::LeaderCannotLeave {})));
+
+        // contributions and activity while in the guild
+        let forage = |deps: &mut MockDeps, id: &str, step: u64| {
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(step * FORAGE_COOLDOWN);
+            let msg = ExecuteMsg::CollectBerries { id: id.to_string() };
+            execute(deps.as_mut(), env, mock_info("sender", &[]), msg)
+        };
+        for (step, id) in ["misty.arch", "misty.arch", "ash.arch"].iter().enumerate() {
+            forage(&mut deps, id, step as u64).unwrap();
+        }
+        let contribute = |amount| ExecuteMsg::ContributeToGuild {
+            id: String::from("misty.arch"),
+            items: vec![Items {
+                kind: ItemKind::Berry,
+                amount,
+            }],
+        };
+        let err = run(&mut deps, contribute(3), &[]);
+        assert!(matches!(err, Err(ContractError::NotEnoughItems { .. })));
+        run(&mut deps, contribute(2), &coins(10, "aarch")).unwrap();
+        let guild_query = QueryMsg::Guild {
+            guild: String::from("rockets"),
+        };
+        let res = query(deps.as_ref(), mock_env(), guild_query.clone()).unwrap();
+        let res: GuildResponse = from_json(res).unwrap();
+        assert_eq!(2, res.guild.members);
+        assert_eq!(
+            vec![Items {
+                kind: ItemKind::Berry,
+                amount: 2
+            }],
+            res.guild.treasury.items
+        );
+        assert_eq!(coins(10, "aarch"), res.guild.treasury.coins);
+        assert_eq!(
+            vec![
+                (String::from("ash.arch"), GuildRole::Leader),
+                (String::from("misty.arch"), GuildRole::Officer),
+            ],
+            res.members
+                .into_iter()
+                .map(|member| (member.player, member.role))
+                .collect::<Vec<_>>()
+        );
+        let msg = QueryMsg::GuildLeaderboard {
+            board: LeaderboardKind::Berries,
+            season: None,
+            start_after: None,
+            limit: None,
+        };
+        let res: LeaderboardResponse =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(
+            vec![LeaderboardEntry {
+                player: String::from("rockets"),
+                score: 3
+            }],
+            res.entries
+        );
+
+        // guild quests pay into the treasury and are claimed by officers
+        let harvest = |xp| ExecuteMsg::SetQuest {
+            id: String::from("harvest"),
+            quest: Quest {
+                objective: Objective::CollectBerries { count: 1 },
+                reward: QuestReward {
+                    berries: 5,
+                    xp,
+                    ..QuestReward::default()
+                },
+                active: true,
+                guild: true,
             },
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            harvest(10),
+        );
+        assert!(matches!(err, Err(ContractError::InvalidGuildReward {})));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            harvest(0),
         )
         .unwrap();
-        let value: bool = from_json(&res).unwrap();
-        assert_eq!(true, value);
+        let res = forage(&mut deps, "ash.arch", 3).unwrap();
+        let completed = res
+            .events
+            .iter()
+            .find(|event| event.ty == "pokearch.quest_completed")
+            .unwrap();
+        assert!(completed
+            .attributes
+            .contains(&Attribute::new("guild", "rockets")));
+        let msg = QueryMsg::QuestProgress {
+            id: String::from("brock.arch"),
+        };
+        let res: Vec<QuestProgressEntry> =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(res.is_empty());
+        let claim = ExecuteMsg::ClaimQuest {
+            id: String::from("misty.arch"),
+            quest: String::from("harvest"),
+        };
+        run(&mut deps, claim.clone(), &[]).unwrap();
+        let err = run(&mut deps, claim, &[]);
+        assert!(matches!(err, Err(ContractError::QuestClaimed { .. })));
+
+        // only the leader pays out of the treasury
+        let payout = |id: &str, amount| ExecuteMsg::GuildPayout {
+            id: id.to_string(),
+            player: String::from("misty.arch"),
+            items: vec![Items {
+                kind: ItemKind::Berry,
+                amount,
+            }],
+            coins: coins(4, "aarch"),
+        };
+        let err = run(&mut deps, payout("misty.arch", 1), &[]);
+        assert!(matches!(err, Err(ContractError::Unauthorized {})));
+        let err = run(&mut deps, payout("ash.arch", 8), &[]);
+        assert!(matches!(err, Err(ContractError::NotEnoughItems { .. })));
+        let res = run(&mut deps, payout("ash.arch", 7), &[]).unwrap();
+        assert_eq!(1, res.messages.len());
+        let msg = QueryMsg::GetPlayer {
+            id: String::from("misty.arch"),
+        };
+        let player: PlayerResponse =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(7, player.player.berries);
+
+        // officers can't kick the leader, the leader can kick anyone
+        let kick = |id: &str, player: &str| ExecuteMsg::KickFromGuild {
+            id: id.to_string(),
+            player: player.to_string(),
+        };
+        let err = run(&mut deps, kick("misty.arch", "ash.arch"), &[]);
+        assert!(matches!(err, Err(ContractError::Unauthorized {})));
+        run(&mut deps, kick("ash.arch", "misty.arch"), &[]).unwrap();
+        let msg = QueryMsg::PlayerGuild {
+            id: String::from("misty.arch"),
+        };
+        let res: Option<String> =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(None, res);
+
+        // the last member leaving disbands the guild and takes the treasury
+        let res = run(&mut deps, leave("ash.arch"), &[]).unwrap();
+        assert_eq!(1, res.messages.len());
+        assert!(query(deps.as_ref(), mock_env(), guild_query).is_err());
+        let msg = QueryMsg::GuildLeaderboard {
+            board: LeaderboardKind::Berries,
+            season: None,
+            start_after: None,
+            limit: None,
+        };
+        let res: LeaderboardResponse =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(res.entries.is_empty());
+
+        // a guild founded under the same id doesn't inherit invites or quests
+        let msg = ExecuteMsg::CreateGuild {
+            id: String::from("misty.arch"),
+            guild: String::from("rockets"),
+            name: String::from("Team Rocket"),
+            max_members: 2,
+        };
+        run(&mut deps, msg, &[]).unwrap();
+        let err = run(&mut deps, join("brock.arch"), &[]);
+        assert!(matches!(err, Err(ContractError::NotInvited { .. })));
+        let msg = QueryMsg::QuestProgress {
+            id: String::from("misty.arch"),
+        };
+        let res: Vec<QuestProgressEntry> =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        let harvest = res.iter().find(|entry| entry.quest == "harvest").unwrap();
+        assert_eq!((0, false), (harvest.progress, harvest.claimed));
+    }
+
+    #[test]
+    fn breeding() {
+        let mut deps = mock_dependencies();
+        setup(&mut deps);
+        let msg = ExecuteMsg::SetSpecies {
+            species: Species {
+                name: String::from("vulpix"),
+                types: vec![String::from("fire")],
+                image: None,
+                collection: None,
+                base_stats: Stats {
+                    attack: 40,
+                    defense: 40,
+                    speed: 60,
+                },
+                required_badge: None,
+                supply: None,
+                variant_odds: None,
+                learnset: vec![],
+            },
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::Register {
+            id: String::from("hello.arch"),
+            starter: String::from("squirtle"),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
+        for species in ["pidgey", "vulpix"] {
+            encounter(&mut deps, "hello.arch", species);
+            let msg = ExecuteMsg::CatchPokemon {
+                id: String::from("hello.arch"),
+                species: species.to_string(),
+                health: 100,
+                curr_pokemon: 0,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
+        }
+        let run = |deps: &mut MockDeps, env: Env, msg: ExecuteMsg| {
+            execute(deps.as_mut(), env, mock_info("sender", &[]), msg)
+        };
+        let breed = |first, second| ExecuteMsg::Breed {
+            id: String::from("hello.arch"),
+            first,
+            second,
+        };
+
+        // parents have to be two different pokemon sharing a type
+        for (first, second) in [(0, 0), (0, 2)] {
+            let err = run(&mut deps, mock_env(), breed(first, second));
+            assert!(matches!(
+                err,
+                Err(ContractError::IncompatibleParents { .. })
+            ));
+        }
+        run(&mut deps, mock_env(), breed(0, 1)).unwrap();
+        let err = run(&mut deps, mock_env(), breed(1, 0));
+        assert!(matches!(err, Err(ContractError::BreedingCooldown { .. })));
+
+        let hatch = ExecuteMsg::HatchEgg {
+            id: String::from("hello.arch"),
+            egg: 1,
+        };
+        let err = run(&mut deps, mock_env(), hatch.clone());
+        assert!(matches!(err, Err(ContractError::EggNotReady { id: 1 })));
+        let eggs = |deps: &MockDeps| {
+            let msg = QueryMsg::Eggs {
+                id: String::from("hello.arch"),
+            };
+            let res: Vec<EggEntry> =
+                from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            res
+        };
+        let res = eggs(&deps);
+        assert_eq!(1, res.len());
+        assert_eq!("squirtle", res[0].egg.species);
+        assert!(!res[0].ready);
+
+        // collecting berries walks the eggs
+        let msg = ExecuteMsg::SetBreedingConfig {
+            config: BreedingConfig {
+                cooldown: SECONDS_PER_DAY,
+                hatch_blocks: 600,
+                hatch_steps: 2,
+            },
+        };
+        let err = run(&mut deps, mock_env(), msg.clone());
+        assert!(matches!(err, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        for step in 0..2 {
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(step * FORAGE_COOLDOWN);
+            let msg = ExecuteMsg::CollectBerries {
+                id: String::from("hello.arch"),
+            };
+            run(&mut deps, env, msg).unwrap();
+        }
+        // eggs are walked no faster than the player can forage
+        let msg = ExecuteMsg::CollectBerries {
+            id: String::from("hello.arch"),
+        };
+        let err = run(&mut deps, mock_env(), msg);
+        assert!(matches!(err, Err(ContractError::ForageCooldown { .. })));
+        let res = eggs(&deps);
+        assert_eq!(2, res[0].egg.steps);
+        assert!(res[0].ready);
+
+        let res = run(&mut deps, mock_env(), hatch.clone()).unwrap();
+        assert_eq!(1, res.messages.len());
+        let err = run(&mut deps, mock_env(), hatch);
+        assert!(matches!(err, Err(ContractError::UnknownEgg { id: 1 })));
+        assert!(eggs(&deps).is_empty());
+        let msg = QueryMsg::GetPlayer {
+            id: String::from("hello.arch"),
+        };
+        let player: PlayerResponse =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        let hatched = &player.player.pokemons[3];
+        assert_eq!("squirtle", hatched.species);
+        // hatching isn't catching, only the two caught pokemon count
+        let msg = QueryMsg::Leaderboard {
+            board: LeaderboardKind::Catches,
+            season: None,
+            start_after: None,
+            limit: None,
+        };
+        let board: LeaderboardResponse =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(2, board.entries[0].score);
+        // every individual value and the nature come from one of the parents
+        let parents: Vec<_> = player.player.pokemons[..2]
+            .iter()
+            .map(|pokemon| pokemon.genes.clone())
+            .collect();
+        let child = hatched.genes.clone();
+        assert!(parents
+            .iter()
+            .any(|genes| genes.ivs.attack == child.ivs.attack));
+        assert!(parents
+            .iter()
+            .any(|genes| genes.ivs.defense == child.ivs.defense));
+        assert!(parents
+            .iter()
+            .any(|genes| genes.ivs.speed == child.ivs.speed));
+        assert!(parents.iter().any(|genes| genes.nature == child.nature));
+
+        let lineage = |token_id| {
+            let msg = QueryMsg::Breeding {
+                collection: None,
+                token_id,
+            };
+            let res: BreedingResponse =
+                from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            res
+        };
+        let res = lineage(hatched.token_id);
+        assert_eq!(None, res.ready_at);
+        assert_eq!(
+            vec![
+                (player.player.pokemons[0].token_id, String::from("squirtle")),
+                (player.player.pokemons[1].token_id, String::from("pidgey")),
+            ],
+            res.parents
+                .into_iter()
+                .map(|parent| (parent.token_id, parent.species))
+                .collect::<Vec<_>>()
+        );
+        let ready_at = mock_env().block.time.plus_seconds(SECONDS_PER_DAY);
+        assert_eq!(
+            Some(ready_at),
+            lineage(player.player.pokemons[0].token_id).ready_at
+        );
+
+        // parents breed again once rested
+        let mut env = mock_env();
+        env.block.time = ready_at;
+        run(&mut deps, env, breed(1, 0)).unwrap();
     }
 
     #[test]
-    fn register() {
+    fn regions() {
         let mut deps = mock_dependencies();
-        let msg = InstantiateMsg {};
-        let info = mock_info("creator", &coins(1000, "earth"));
-
-        // we can just call .unwrap() to assert this was a success
-        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
-
+        setup(&mut deps);
         let msg = ExecuteMsg::Register {
-            id: "hello.arch".to_string(),
+            id: String::from("hello.arch"),
+            starter: String::from("bulbasaur"),
         };
-        let info = mock_info("sender", &[]);
-
-        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
+        execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
 
-        let mut pokemon: Vec<Pokemon> = Vec::new();
-        pokemon.push(Pokemon {
-            token_id: 0,
-            index: 0,
-            health: 100,
-        });
+        let spawn = |species: &str, min_level, max_level, window| Spawn {
+            species: species.to_string(),
+            weight: 1,
+            min_level,
+            max_level,
+            window,
+        };
+        let set_region = |id: &str, spawns| ExecuteMsg::SetRegion {
+            id: id.to_string(),
+            region: Region {
+                name: id.to_string(),
+                spawns,
+                required_badge: None,
+            },
+        };
+        // the mock block is at 02:23 UTC
+        let day = Some(TimeWindow { start: 6, end: 18 });
+        let night = Some(TimeWindow { start: 22, end: 4 });
+        let route = set_region(
+            "route",
+            vec![spawn("pidgey", 2, 4, night), spawn("charmander", 1, 1, day)],
+        );
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            route.clone(),
+        );
+        assert!(matches!(err, Err(ContractError::Unauthorized {})));
+        for (spawns, invalid) in [
+            (vec![spawn("pidgey", 0, 1, None)], "pidgey"),
+            (vec![spawn("pidgey", 3, 2, None)], "pidgey"),
+            (
+                vec![spawn(
+                    "squirtle",
+                    1,
+                    1,
+                    Some(TimeWindow { start: 24, end: 2 }),
+                )],
+                "squirtle",
+            ),
+        ] {
+            let msg = set_region("route", spawns);
+            let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+            assert!(
+                matches!(err, Err(ContractError::InvalidSpawn { species }) if species == invalid)
+            );
+        }
+        let msg = set_region("route", vec![spawn("mew", 1, 1, None)]);
+        let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        assert!(matches!(err, Err(ContractError::UnknownSpecies { .. })));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), route).unwrap();
+        let msg = set_region("meadow", vec![spawn("squirtle", 1, 1, day)]);
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Regions {}).unwrap();
+        let regions: Vec<RegionEntry> = from_json(res).unwrap();
+        assert_eq!(
+            vec!["meadow", "route"],
+            regions.iter().map(|entry| &entry.id).collect::<Vec<_>>()
+        );
 
-        let player_data = Player {
+        let explore = |region: &str| ExecuteMsg::Explore {
             id: String::from("hello.arch"),
-            potions: 0,
-            berries: 0,
-            default_pokemon: 0,
-            pokemons: pokemon,
+            region: region.to_string(),
         };
-
-        let res = query(
-            deps.as_ref(),
+        let err = execute(
+            deps.as_mut(),
             mock_env(),
-            QueryMsg::GetPlayer {
+            mock_info("sender", &[]),
+            explore("cave"),
+        );
+        assert!(matches!(err, Err(ContractError::UnknownRegion { .. })));
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            explore("meadow"),
+        );
+        assert!(matches!(err, Err(ContractError::NothingSpawns { .. })));
+        let encounter = |deps: &MockDeps| {
+            let msg = QueryMsg::GetPlayer {
                 id: String::from("hello.arch"),
+            };
+            let res: PlayerResponse =
+                from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            res.player.encounter.unwrap()
+        };
+
+        let at = |seconds| {
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(seconds);
+            env
+        };
+
+        // only pidgey is out at night, at any level of its range
+        for step in 0..10 {
+            execute(
+                deps.as_mut(),
+                at(step * EXPLORE_COOLDOWN),
+                mock_info("sender", &[]),
+                explore("route"),
+            )
+            .unwrap();
+            let encounter = encounter(&deps);
+            assert_eq!("pidgey", encounter.species);
+            assert!((2..=4).contains(&encounter.level));
+        }
+
+        // running from a pokemon takes a while, unless a ticket is spent
+        let err = execute(
+            deps.as_mut(),
+            at(10 * EXPLORE_COOLDOWN - 1),
+            mock_info("sender", &[]),
+            explore("route"),
+        );
+        assert!(matches!(err, Err(ContractError::ExploreCooldown { .. })));
+        let msg = ExecuteMsg::SetCheckInSchedule {
+            schedule: CheckInSchedule {
+                rewards: vec![Items {
+                    kind: ItemKind::Ticket,
+                    amount: 1,
+                }],
+                protection: None,
             },
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::CheckIn {
+            id: String::from("hello.arch"),
+            protect_streak: false,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
+        execute(
+            deps.as_mut(),
+            at(10 * EXPLORE_COOLDOWN - 1),
+            mock_info("sender", &[]),
+            explore("route"),
         )
         .unwrap();
-        let value: PlayerResponse = from_json(&res).unwrap();
-        assert_eq!(
-            PlayerResponse {
-                player: player_data
-            },
-            value
+        let msg = QueryMsg::GetPlayer {
+            id: String::from("hello.arch"),
+        };
+        let res: PlayerResponse =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(0, res.player.tickets);
+        let err = execute(
+            deps.as_mut(),
+            at(10 * EXPLORE_COOLDOWN),
+            mock_info("sender", &[]),
+            explore("route"),
         );
-    }
-
-    #[test]
-    fn test_game() {
-        let mut deps = mock_dependencies();
-
-        let msg = InstantiateMsg {};
-        let info = mock_info("creator", &coins(1000, "earth"));
+        assert!(matches!(err, Err(ContractError::ExploreCooldown { .. })));
 
-        // we can just call .unwrap() to assert this was a success
-        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        execute(
+            deps.as_mut(),
+            at(8 * 60 * 60),
+            mock_info("sender", &[]),
+            explore("meadow"),
+        )
+        .unwrap();
+        assert_eq!("squirtle", encounter(&deps).species);
 
-        let msg = ExecuteMsg::SetNFTContract {
-            addr: "nft".to_string(),
-            token_uri: String::from("hello"),
+        // regions can be closed to players without the right badge
+        let msg = ExecuteMsg::SetRegion {
+            id: String::from("plateau"),
+            region: Region {
+                name: String::from("Indigo Plateau"),
+                spawns: vec![spawn("pidgey", 50, 50, None)],
+                required_badge: Some(String::from("boulder")),
+            },
         };
-        let info = mock_info("creator", &[]);
-
-        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let err = execute(
+            deps.as_mut(),
+            at(SECONDS_PER_DAY),
+            mock_info("sender", &[]),
+            explore("plateau"),
+        );
+        assert!(matches!(err, Err(ContractError::MissingBadge { .. })));
 
-        let msg = ExecuteMsg::Register {
+        // only the encountered pokemon can be caught, and only once
+        execute(
+            deps.as_mut(),
+            at(SECONDS_PER_DAY),
+            mock_info("sender", &[]),
+            explore("route"),
+        )
+        .unwrap();
+        let found = encounter(&deps);
+        let catch = |species: &str| ExecuteMsg::CatchPokemon {
             id: String::from("hello.arch"),
+            species: species.to_string(),
+            health: 100,
+            curr_pokemon: 0,
         };
-        let info = mock_info("sender", &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            catch("squirtle"),
+        );
+        assert!(matches!(err, Err(ContractError::NotEncountered { .. })));
+        // nobody else can spend the player's encounter
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("thief", &[]),
+            catch("pidgey"),
+        );
+        assert!(matches!(err, Err(ContractError::NotPlayerOwner { .. })));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            catch("pidgey"),
+        )
+        .unwrap();
+        let msg = QueryMsg::GetPlayer {
+            id: String::from("hello.arch"),
+        };
+        let res: PlayerResponse =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(None, res.player.encounter);
+        // the catch claims exactly what was found
+        assert_eq!(found.level, res.player.pokemons[1].level);
+        assert_eq!(found.genes, res.player.pokemons[1].genes);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            catch("pidgey"),
+        );
+        assert!(matches!(err, Err(ContractError::NotEncountered { .. })));
 
-        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        // species can require a badge too, and then don't spawn without it
+        let msg = QueryMsg::GetSpecies {
+            name: String::from("squirtle"),
+        };
+        let mut squirtle: Species =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        squirtle.required_badge = Some(String::from("cascade"));
+        let msg = ExecuteMsg::SetSpecies { species: squirtle };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = set_region("lake", vec![spawn("squirtle", 1, 1, None)]);
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let err = execute(
+            deps.as_mut(),
+            at(SECONDS_PER_DAY),
+            mock_info("sender", &[]),
+            explore("lake"),
+        );
+        assert!(matches!(err, Err(ContractError::NothingSpawns { .. })));
+    }
 
-        let msg = ExecuteMsg::CatchPokemon {
-            id: String::from("hello.arch"),
-            token_uri: String::from("hello"),
-            health: 32,
+    #[test]
+    fn legendaries() {
+        let mut deps = mock_dependencies();
+        setup(&mut deps);
+        let now = mock_env().block.time;
+        let legendary = |name: &str, supply| ExecuteMsg::SetSpecies {
+            species: Species {
+                name: name.to_string(),
+                types: vec![String::from("psychic")],
+                image: None,
+                collection: None,
+                base_stats: Stats {
+                    attack: 110,
+                    defense: 90,
+                    speed: 130,
+                },
+                required_badge: None,
+                supply: Some(supply),
+                variant_odds: None,
+                learnset: vec![],
+            },
+        };
+        let mewtwo = legendary(
+            "mewtwo",
+            Supply {
+                max: 2,
+                per_player: Some(1),
+                start: None,
+                end: None,
+            },
+        );
+        let mew = legendary(
+            "mew",
+            Supply {
+                max: 10,
+                per_player: None,
+                start: Some(now.plus_seconds(60 * 60)),
+                end: Some(now.plus_seconds(2 * 60 * 60)),
+            },
+        );
+        for msg in [mewtwo, mew] {
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+        for species in ["mewtwo", "mew"] {
+            spawn_only(&mut deps, species);
+        }
+        for id in ["ash.arch", "misty.arch", "brock.arch"] {
+            let msg = ExecuteMsg::Register {
+                id: id.to_string(),
+                starter: String::from("bulbasaur"),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
+        }
+        let run = |deps: &mut MockDeps, env: Env, msg: ExecuteMsg| {
+            execute(deps.as_mut(), env, mock_info("sender", &[]), msg)
+        };
+        let explore = |id: &str, region: &str| ExecuteMsg::Explore {
+            id: id.to_string(),
+            region: region.to_string(),
+        };
+        let catch = |id: &str, species: &str| ExecuteMsg::CatchPokemon {
+            id: id.to_string(),
+            species: species.to_string(),
+            health: 100,
             curr_pokemon: 0,
         };
-        let info = mock_info("sender", &[]);
+        let supply = |deps: &MockDeps, player: Option<&str>| {
+            let msg = QueryMsg::Supply {
+                species: String::from("mewtwo"),
+                player: player.map(String::from),
+            };
+            let res: SupplyResponse =
+                from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            res
+        };
+        assert_eq!(
+            SupplyResponse {
+                caught: 0,
+                remaining: Some(2),
+                player_remaining: Some(1),
+            },
+            supply(&deps, Some("ash.arch"))
+        );
 
-        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(1, res.messages.len());
+        for id in ["ash.arch", "misty.arch", "brock.arch"] {
+            run(&mut deps, mock_env(), explore(id, "mewtwo")).unwrap();
+        }
+        run(&mut deps, mock_env(), catch("ash.arch", "mewtwo")).unwrap();
+        assert_eq!(
+            SupplyResponse {
+                caught: 1,
+                remaining: Some(1),
+                player_remaining: Some(0),
+            },
+            supply(&deps, Some("ash.arch"))
+        );
+        // players at their cap no longer run into it
+        let err = run(&mut deps, mock_env(), explore("ash.arch", "mewtwo"));
+        assert!(matches!(err, Err(ContractError::NothingSpawns { .. })));
 
-        let mut pokemon: Vec<Pokemon> = Vec::new();
-        pokemon.push(Pokemon {
-            token_id: 0,
-            index: 0,
-            health: 32,
-        });
+        // the last one goes to whoever catches it first
+        run(&mut deps, mock_env(), catch("misty.arch", "mewtwo")).unwrap();
+        let err = run(&mut deps, mock_env(), catch("brock.arch", "mewtwo"));
+        assert!(matches!(err, Err(ContractError::SoldOut { .. })));
+        let at = |seconds| {
+            let mut env = mock_env();
+            env.block.time = now.plus_seconds(seconds);
+            env
+        };
+        let err = run(
+            &mut deps,
+            at(EXPLORE_COOLDOWN),
+            explore("brock.arch", "mewtwo"),
+        );
+        assert!(matches!(err, Err(ContractError::NothingSpawns { .. })));
+        assert_eq!(Some(0), supply(&deps, None).remaining);
 
-        pokemon.push(Pokemon {
-            token_id: 1,
-            index: 1,
-            health: 100,
-        });
+        // mew only shows up during its window
+        for (env, spawns) in [
+            (at(EXPLORE_COOLDOWN), false),
+            (at(90 * 60), true),
+            (at(2 * 60 * 60), false),
+        ] {
+            let res = run(&mut deps, env, explore("brock.arch", "mew"));
+            assert_eq!(spawns, res.is_ok());
+        }
 
-        let player_data = Player {
+        // starters and eggs count against the supply as well
+        let msg = ExecuteMsg::SetSpecies {
+            species: Species {
+                name: String::from("squirtle"),
+                types: vec![String::from("normal")],
+                image: None,
+                collection: None,
+                base_stats: Stats {
+                    attack: 50,
+                    defense: 50,
+                    speed: 50,
+                },
+                required_badge: None,
+                supply: Some(Supply {
+                    max: 1,
+                    per_player: None,
+                    start: None,
+                    end: None,
+                }),
+                variant_odds: None,
+                learnset: vec![],
+            },
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let register = |id: &str| ExecuteMsg::Register {
+            id: id.to_string(),
+            starter: String::from("squirtle"),
+        };
+        run(&mut deps, mock_env(), register("gary.arch")).unwrap();
+        let err = run(&mut deps, mock_env(), register("red.arch"));
+        assert!(matches!(err, Err(ContractError::SoldOut { .. })));
+        encounter(&mut deps, "gary.arch", "pidgey");
+        run(&mut deps, mock_env(), catch("gary.arch", "pidgey")).unwrap();
+        let msg = ExecuteMsg::Breed {
+            id: String::from("gary.arch"),
+            first: 0,
+            second: 1,
+        };
+        let err = run(&mut deps, mock_env(), msg);
+        assert!(matches!(err, Err(ContractError::SoldOut { .. })));
+    }
+
+    #[test]
+    fn variants() {
+        let mut deps = mock_dependencies();
+        setup(&mut deps);
+        let msg = ExecuteMsg::Register {
             id: String::from("hello.arch"),
-            potions: 0,
-            berries: 0,
-            default_pokemon: 0,
-            pokemons: pokemon,
+            starter: String::from("bulbasaur"),
         };
+        execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap();
 
-        let res = query(
-            deps.as_ref(),
+        let odds = VariantOdds {
+            shiny: 0,
+            alternate: 0,
+        };
+        let msg = ExecuteMsg::SetVariantOdds { odds };
+        let err = execute(
+            deps.as_mut(),
             mock_env(),
-            QueryMsg::GetPlayer {
+            mock_info("sender", &[]),
+            msg.clone(),
+        );
+        assert!(matches!(err, Err(ContractError::Unauthorized {})));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // species odds replace the global ones, one in one always rolls
+        let set_odds = |deps: &mut MockDeps, odds: Option<VariantOdds>| {
+            let msg = ExecuteMsg::SetSpecies {
+                species: Species {
+                    name: String::from("pidgey"),
+                    types: vec![String::from("normal"), String::from("flying")],
+                    image: None,
+                    collection: None,
+                    base_stats: Stats {
+                        attack: 50,
+                        defense: 50,
+                        speed: 50,
+                    },
+                    required_badge: None,
+                    supply: None,
+                    variant_odds: odds,
+                    learnset: vec![],
+                },
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        };
+        // the variant is fixed when exploring, the odds at catch time don't matter
+        let mut catch_with = |odds: Option<VariantOdds>| {
+            set_odds(&mut deps, odds);
+            encounter(&mut deps, "hello.arch", "pidgey");
+            set_odds(&mut deps, None);
+            let msg = ExecuteMsg::CatchPokemon {
                 id: String::from("hello.arch"),
-            },
+                species: String::from("pidgey"),
+                health: 100,
+                curr_pokemon: 0,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap()
+        };
+        let res = catch_with(Some(VariantOdds {
+            shiny: 1,
+            alternate: 1,
+        }));
+        let catch = res
+            .events
+            .iter()
+            .find(|event| event.ty == "pokearch.catch")
+            .unwrap();
+        assert!(catch
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "variant" && attr.value == "shiny"));
+        catch_with(Some(VariantOdds {
+            shiny: 0,
+            alternate: 1,
+        }));
+        catch_with(None);
+
+        let player: PlayerResponse = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetPlayer {
+                    id: String::from("hello.arch"),
+                },
+            )
+            .unwrap(),
         )
         .unwrap();
-        let value: PlayerResponse = from_json(&res).unwrap();
+        let variants: Vec<_> = player.player.pokemons.iter().map(|p| p.variant).collect();
         assert_eq!(
-            PlayerResponse {
-                player: player_data
-            },
-            value
+            vec![None, Some(Variant::Shiny), Some(Variant::Alternate), None],
+            variants
         );
 
-        let msg = ExecuteMsg::UpdateHealth {
-            id: String::from("hello.arch"),
-            token_id: 0,
+        let msg = QueryMsg::TokenMetadata {
+            collection: None,
+            token_id: 2,
+            data_uri: None,
         };
-        let info = mock_info("sender", &[]);
+        let res: TokenMetadataResponse =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!("Shiny Pidgey #2", res.metadata.name);
+        assert!(res
+            .metadata
+            .attributes
+            .iter()
+            .any(|attr| attr.trait_type == "Variant" && attr.value == "shiny"));
 
-        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
+        for species in [Some(String::from("pidgey")), None] {
+            let msg = QueryMsg::VariantCounts { species };
+            let res: VariantCountsResponse =
+                from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            assert_eq!(
+                VariantCountsResponse {
+                    shiny: 1,
+                    alternate: 1,
+                },
+                res
+            );
+        }
+    }
 
-        let mut pokemon: Vec<Pokemon> = Vec::new();
-        pokemon.push(Pokemon {
-            token_id: 0,
-            index: 0,
-            health: 100,
-        });
+    #[test]
+    fn individual_values() {
+        let mut deps = mock_dependencies();
+        setup(&mut deps);
+        for (id, sender) in [("hello.arch", "sender"), ("rival.arch", "rival")] {
+            let msg = ExecuteMsg::Register {
+                id: id.to_string(),
+                starter: String::from("bulbasaur"),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).unwrap();
+        }
 
-        pokemon.push(Pokemon {
-            token_id: 1,
-            index: 1,
-            health: 100,
-        });
+        // two of the same species caught in the same block still differ
+        let first = genes(&deps, 1);
+        let second = genes(&deps, 2);
+        assert_ne!(first, second);
+        for genes in [&first, &second] {
+            let ivs = [genes.ivs.attack, genes.ivs.defense, genes.ivs.speed];
+            assert!(ivs.iter().all(|iv| (0..=MAX_IV).contains(iv)));
+        }
 
-        let player_data = Player {
-            id: String::from("hello.arch"),
-            potions: 0,
-            berries: 0,
-            default_pokemon: 0,
-            pokemons: pokemon,
+        let base = Stats {
+            attack: 50,
+            defense: 50,
+            speed: 50,
+        };
+        let perfect = Genes {
+            ivs: Stats {
+                attack: MAX_IV,
+                defense: MAX_IV,
+                speed: MAX_IV,
+            },
+            nature: Nature::Lonely,
         };
+        assert_eq!(
+            Stats {
+                attack: 71,
+                defense: 58,
+                speed: 65,
+            },
+            perfect.apply(&base, 1)
+        );
 
-        let res = query(
-            deps.as_ref(),
+        let msg = QueryMsg::Genes {
+            collection: None,
+            token_id: 3,
+        };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+    }
+
+    #[test]
+    fn moves() {
+        let mut deps = mock_dependencies();
+        setup(&mut deps);
+        let set_move = |id: &str, pp| ExecuteMsg::SetMove {
+            id: id.to_string(),
+            details: Move {
+                kind: String::from("grass"),
+                power: 40,
+                accuracy: 100,
+                pp,
+                effect: None,
+            },
+        };
+        let err = execute(
+            deps.as_mut(),
             mock_env(),
-            QueryMsg::GetPlayer {
-                id: String::from("hello.arch"),
+            mock_info("sender", &[]),
+            set_move("tackle", 35),
+        );
+        assert!(matches!(err, Err(ContractError::Unauthorized {})));
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            set_move("tackle", 0),
+        );
+        assert!(matches!(err, Err(ContractError::InvalidMove { .. })));
+        for id in ["tackle", "growl", "leech_seed", "vine_whip", "razor_leaf"] {
+            let info = mock_info("creator", &[]);
+            execute(deps.as_mut(), mock_env(), info, set_move(id, 10)).unwrap();
+        }
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Moves {}).unwrap();
+        assert_eq!(5, from_json::<Vec<MoveEntry>>(res).unwrap().len());
+
+        let species = |learnset: &[(i32, &str)]| ExecuteMsg::SetSpecies {
+            species: Species {
+                name: String::from("bulbasaur"),
+                types: vec![String::from("grass")],
+                image: None,
+                collection: None,
+                base_stats: Stats {
+                    attack: 50,
+                    defense: 50,
+                    speed: 50,
+                },
+                required_badge: None,
+                supply: None,
+                variant_odds: None,
+                learnset: learnset
+                    .iter()
+                    .map(|(level, id)| LearnableMove {
+                        level: *level,
+                        id: id.to_string(),
+                    })
+                    .collect(),
             },
-        )
-        .unwrap();
-        let value: PlayerResponse = from_json(&res).unwrap();
+        };
+        let info = mock_info("creator", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, species(&[(1, "surf")]));
+        assert!(matches!(err, Err(ContractError::UnknownMove { .. })));
+        let learnset = species(&[
+            (1, "tackle"),
+            (2, "vine_whip"),
+            (1, "growl"),
+            (1, "leech_seed"),
+            (1, "razor_leaf"),
+        ]);
+        let info = mock_info("creator", &[]);
+        execute(deps.as_mut(), mock_env(), info, learnset).unwrap();
+        for (id, sender) in [("hello.arch", "sender"), ("rival.arch", "rival")] {
+            let msg = ExecuteMsg::Register {
+                id: id.to_string(),
+                starter: String::from("bulbasaur"),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).unwrap();
+        }
+
+        // new pokemon know the last moves their level allows
+        let known = |deps: &MockDeps| {
+            let player = query::get_player(deps.as_ref(), String::from("hello.arch")).unwrap();
+            player.player.pokemons[0].moves.clone()
+        };
+        let ids = |deps: &MockDeps| -> Vec<String> {
+            known(deps).into_iter().map(|known| known.id).collect()
+        };
         assert_eq!(
-            PlayerResponse {
-                player: player_data
-            },
-            value
+            vec!["tackle", "growl", "leech_seed", "razor_leaf"],
+            ids(&deps)
         );
-        let msg = ExecuteMsg::CatchPokemon {
+
+        let run = |deps: &mut MockDeps, msg: ExecuteMsg| {
+            execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg)
+        };
+        let learn = |move_id: &str| ExecuteMsg::LearnMove {
             id: String::from("hello.arch"),
-            token_uri: String::from("hello"),
-            health: 32,
-            curr_pokemon: 0,
+            pokemon: 0,
+            move_id: move_id.to_string(),
         };
-        let info = mock_info("sender", &[]);
-
-        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(1, res.messages.len());
+        let forget = |move_id: &str| ExecuteMsg::ForgetMove {
+            id: String::from("hello.arch"),
+            pokemon: 0,
+            move_id: move_id.to_string(),
+        };
+        let err = run(&mut deps, learn("vine_whip"));
+        assert!(matches!(err, Err(ContractError::CannotLearnMove { .. })));
+        let battle = ExecuteMsg::RecordBattle {
+            winner: String::from("hello.arch"),
+            loser: String::from("rival.arch"),
+            pokemon: 0,
+        };
+        for _ in 0..4 {
+            let info = mock_info("creator", &[]);
+            execute(deps.as_mut(), mock_env(), info, battle.clone()).unwrap();
+        }
+        let err = run(&mut deps, learn("vine_whip"));
+        assert!(matches!(err, Err(ContractError::TooManyMoves { max: 4 })));
+        let err = run(&mut deps, learn("tackle"));
+        assert!(matches!(err, Err(ContractError::MoveAlreadyKnown { .. })));
+        run(&mut deps, forget("growl")).unwrap();
+        let err = run(&mut deps, forget("growl"));
+        assert!(matches!(err, Err(ContractError::MoveNotKnown { .. })));
+        run(&mut deps, learn("vine_whip")).unwrap();
+        assert_eq!(
+            vec!["tackle", "leech_seed", "razor_leaf", "vine_whip"],
+            ids(&deps)
+        );
 
+        // battling spends PP and healing replenishes it
+        let msg = ExecuteMsg::SetGym {
+            id: String::from("pewter"),
+            gym: Gym {
+                leader: String::from("brock"),
+                team: vec![GymPokemon {
+                    species: String::from("pidgey"),
+                    level: 1,
+                }],
+                min_level: 1,
+                required_badge: None,
+                badge: String::from("boulder"),
+            },
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::ChallengeGym {
+            id: String::from("hello.arch"),
+            gym: String::from("pewter"),
+            party: vec![0],
+        };
+        run(&mut deps, msg).unwrap();
+        let spent: u32 = known(&deps).iter().map(|known| 10 - known.pp).sum();
+        assert!(spent > 0);
+        let msg = ExecuteMsg::VisitPokemonCenter {
+            id: String::from("hello.arch"),
+        };
+        run(&mut deps, msg).unwrap();
+        assert!(known(&deps).iter().all(|known| known.pp == 10));
     }
 }
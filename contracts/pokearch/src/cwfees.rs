@@ -12,6 +12,19 @@ pub struct MsgRegisterAsGranter {
     pub granting_contract: ::prost::alloc::string::String,
 }
 
+/// MsgExecuteContract submits the given message data to a smart contract.
+/// Mirrors cosmwasm.wasm.v1.MsgExecuteContract without the funds, which grants don't look at.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgExecuteContract {
+    #[prost(string, tag = "1")]
+    pub sender: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub contract: ::prost::alloc::string::String,
+    #[prost(bytes = "vec", tag = "3")]
+    pub msg: ::prost::alloc::vec::Vec<u8>,
+}
+
 /// It's the message you have to use in your sudo entrypoint,
 /// the x/cwfees module sends these message as a sudo call to
 /// your contract. Based on that information the contract
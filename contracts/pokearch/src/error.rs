@@ -12,6 +12,124 @@ pub enum ContractError {
     DisallowedMessage(String),
     #[error("not allowed to spend fees on contract {0}")]
     DisallowedContract(String),
+    #[error("pokemon {index} not found")]
+    PokemonNotFound { index: i32 },
+    #[error("pokemon {index} has fainted")]
+    PokemonFainted { index: i32 },
+    #[error("pokemon {index} has not fainted")]
+    PokemonNotFainted { index: i32 },
+    #[error("no revives left")]
+    NoRevives {},
+    #[error("pokemon center is on cooldown until {ready_at}")]
+    PokemonCenterCooldown { ready_at: u64 },
+    #[error("the fee is exactly {fee}")]
+    WrongFee { fee: String },
+    #[error("no funds are due")]
+    UnexpectedFunds {},
+    #[error("foraging is on cooldown until {ready_at}")]
+    ForageCooldown { ready_at: u64 },
+    #[error("exploring is on cooldown until {ready_at}")]
+    ExploreCooldown { ready_at: u64 },
+    #[error("player {id} is not owned by the sender")]
+    NotPlayerOwner { id: String },
+    #[error("unknown species {species}")]
+    UnknownSpecies { species: String },
+    #[error("collection {id} is not registered")]
+    UnknownCollection { id: String },
+    #[error("{species} is not a starter")]
+    InvalidStarter { species: String },
+    #[error("no starters have been set")]
+    NoStarters {},
+    #[error("{id} does not resolve to and is not owned by the sender")]
+    NameNotOwned { id: String },
+    #[error("ArchID verification is not configured")]
+    ArchIdNotConfigured {},
+    #[error("rewards split must not exceed one")]
+    InvalidRewardsSplit {},
+    #[error("the gas tank cannot cover the requested fees")]
+    GasTankEmpty {},
+    #[error("premium actions are not covered by fee grants")]
+    PremiumNotGranted {},
+    #[error("the prize pool cannot cover {amount}")]
+    PrizePoolEmpty { amount: String },
+    #[error("unknown reply id {0}")]
+    UnknownReply(u64),
+    #[error("{feature} is paused")]
+    Paused { feature: String },
+    #[error("already checked in today, come back after {next}")]
+    AlreadyCheckedIn { next: u64 },
+    #[error("the streak cannot be protected")]
+    StreakNotProtected {},
+    #[error("unknown gym {id}")]
+    UnknownGym { id: String },
+    #[error("the {badge} badge is required")]
+    MissingBadge { badge: String },
+    #[error("pokemon {index} has to be at least level {required}")]
+    LevelTooLow { index: i32, required: i32 },
+    #[error("a party needs between one and {max} different pokemon")]
+    InvalidParty { max: usize },
+    #[error("unknown guild {id}")]
+    UnknownGuild { id: String },
+    #[error("guild {id} already exists")]
+    GuildTaken { id: String },
+    #[error("guilds have room for one to {max} members")]
+    InvalidGuildSize { max: u32 },
+    #[error("guild {id} is full")]
+    GuildFull { id: String },
+    #[error("{id} is already in a guild")]
+    AlreadyInGuild { id: String },
+    #[error("{id} is not in the guild")]
+    NotInGuild { id: String },
+    #[error("{id} has not been invited to guild {guild}")]
+    NotInvited { id: String, guild: String },
+    #[error("the leader has to hand over the guild before leaving")]
+    LeaderCannotLeave {},
+    #[error("not enough {item}")]
+    NotEnoughItems { item: String },
+    #[error("amounts must be positive")]
+    InvalidAmount {},
+    #[error("guild quests can only reward items")]
+    InvalidGuildReward {},
+    #[error("unknown quest {id}")]
+    UnknownQuest { id: String },
+    #[error("quest {id} is not complete")]
+    QuestIncomplete { id: String },
+    #[error("quest {id} has already been claimed")]
+    QuestClaimed { id: String },
+    #[error("pokemon {first} and {second} cannot breed")]
+    IncompatibleParents { first: i32, second: i32 },
+    #[error("pokemon {index} can breed again after {ready_at}")]
+    BreedingCooldown { index: i32, ready_at: u64 },
+    #[error("unknown egg {id}")]
+    UnknownEgg { id: u64 },
+    #[error("egg {id} is not ready to hatch")]
+    EggNotReady { id: u64 },
+    #[error("unknown region {id}")]
+    UnknownRegion { id: String },
+    #[error("invalid spawn of {species}")]
+    InvalidSpawn { species: String },
+    #[error("nothing spawns in {region} right now")]
+    NothingSpawns { region: String },
+    #[error("no wild {species} has been encountered")]
+    NotEncountered { species: String },
+    #[error("no {species} are left")]
+    SoldOut { species: String },
+    #[error("players can catch at most {max} {species}")]
+    CatchLimit { species: String, max: u32 },
+    #[error("unknown move {id}")]
+    UnknownMove { id: String },
+    #[error("invalid move {id}")]
+    InvalidMove { id: String },
+    #[error("{species} can't learn {id} at its level")]
+    CannotLearnMove { species: String, id: String },
+    #[error("the pokemon already knows {id}")]
+    MoveAlreadyKnown { id: String },
+    #[error("the pokemon doesn't know {id}")]
+    MoveNotKnown { id: String },
+    #[error("pokemon know at most {max} moves, one has to be forgotten first")]
+    TooManyMoves { max: usize },
+    #[error("invalid battle")]
+    InvalidBattle {},
     #[error("decode error")]
     DecodeError(#[from] ::prost::DecodeError),
 }
@@ -0,0 +1,129 @@
+use cosmwasm_std::{
+    to_json_binary, Addr, Coin, CosmosMsg, CustomQuery, QuerierWrapper, StdResult, WasmMsg,
+};
+use serde::de::DeserializeOwned;
+
+use crate::msg::{
+    CollectionsResponse, ExecuteMsg, LeaderboardResponse, PauseStatusResponse, PlayerResponse,
+    PokemonMetadata, QueryMsg, TokenMetadataResponse,
+};
+use crate::state::{LeaderboardKind, Species};
+
+/// A deployed pokearch contract, for building its messages and queries without hand-written
+/// JSON.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PokeArchContract(pub Addr);
+
+impl PokeArchContract {
+    pub fn addr(&self) -> Addr {
+        self.0.clone()
+    }
+
+    pub fn call<C>(&self, msg: impl Into<ExecuteMsg>) -> StdResult<CosmosMsg<C>> {
+        self.call_with_funds(msg, vec![])
+    }
+
+    /// Executes `msg` with `funds` attached, e.g. to pay for a Pokemon Center visit.
+    pub fn call_with_funds<C>(
+        &self,
+        msg: impl Into<ExecuteMsg>,
+        funds: Vec<Coin>,
+    ) -> StdResult<CosmosMsg<C>> {
+        Ok(WasmMsg::Execute {
+            contract_addr: self.addr().into(),
+            msg: to_json_binary(&msg.into())?,
+            funds,
+        }
+        .into())
+    }
+
+    pub fn query<Q: CustomQuery, T: DeserializeOwned>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        msg: &QueryMsg,
+    ) -> StdResult<T> {
+        querier.query_wasm_smart(self.addr(), msg)
+    }
+
+    pub fn player<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        id: impl Into<String>,
+    ) -> StdResult<PlayerResponse> {
+        self.query(querier, &QueryMsg::GetPlayer { id: id.into() })
+    }
+
+    pub fn species<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        name: impl Into<String>,
+    ) -> StdResult<Species> {
+        self.query(querier, &QueryMsg::GetSpecies { name: name.into() })
+    }
+
+    pub fn starters<Q: CustomQuery>(&self, querier: &QuerierWrapper<Q>) -> StdResult<Vec<String>> {
+        self.query(querier, &QueryMsg::GetStarters {})
+    }
+
+    /// The default collection pokemon are minted into.
+    pub fn nft_contract<Q: CustomQuery>(&self, querier: &QuerierWrapper<Q>) -> StdResult<Addr> {
+        self.query(querier, &QueryMsg::GetNFTContract {})
+    }
+
+    pub fn collections<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+    ) -> StdResult<CollectionsResponse> {
+        self.query(querier, &QueryMsg::Collections {})
+    }
+
+    pub fn pokemon_metadata<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        collection: impl Into<String>,
+        token_id: i32,
+    ) -> StdResult<PokemonMetadata> {
+        let msg = QueryMsg::PokemonMetadata {
+            collection: collection.into(),
+            token_id,
+        };
+        self.query(querier, &msg)
+    }
+
+    /// Metadata of a token in the default collection, along with its `token_uri`.
+    pub fn token_metadata<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        token_id: i32,
+    ) -> StdResult<TokenMetadataResponse> {
+        let msg = QueryMsg::TokenMetadata {
+            collection: None,
+            token_id,
+            data_uri: Some(true),
+        };
+        self.query(querier, &msg)
+    }
+
+    pub fn pause_status<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+    ) -> StdResult<PauseStatusResponse> {
+        self.query(querier, &QueryMsg::PauseStatus {})
+    }
+
+    /// The top of the current season's board.
+    pub fn leaderboard<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        board: LeaderboardKind,
+        limit: Option<u32>,
+    ) -> StdResult<LeaderboardResponse> {
+        let msg = QueryMsg::Leaderboard {
+            board,
+            season: None,
+            start_after: None,
+            limit,
+        };
+        self.query(querier, &msg)
+    }
+}
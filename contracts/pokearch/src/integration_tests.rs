@@ -0,0 +1,351 @@
+use archway_bindings::{ArchwayMsg, ArchwayQuery};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+};
+use cw_multi_test::{Contract, ContractWrapper, Executor};
+use cw_storage_plus::Map;
+
+use crate::archid::{QueryMsg as ArchIdQueryMsg, ResolveRecordResponse};
+use crate::helpers::PokeArchContract;
+use crate::msg::{CollectionsResponse, ExecuteMsg, NewCollection, PlayerResponse};
+use crate::multitest::{PokeArchApp, PokeArchBuilder};
+use crate::state::{Region, Spawn, Species, Stats};
+use crate::ContractError;
+
+const OWNER: &str = "owner";
+const ALICE: &str = "alice";
+const BOB: &str = "bob";
+
+/// A stand-in for the ArchID registry that resolves whatever records it is told about.
+mod mock_registry {
+    use super::*;
+
+    const RECORDS: Map<String, String> = Map::new("records");
+
+    #[cw_serde]
+    pub struct SetRecord {
+        pub name: String,
+        pub address: String,
+    }
+
+    pub fn instantiate(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        Ok(Response::default())
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: SetRecord,
+    ) -> StdResult<Response> {
+        RECORDS.save(deps.storage, msg.name, &msg.address)?;
+        Ok(Response::default())
+    }
+
+    pub fn query(deps: Deps, env: Env, msg: ArchIdQueryMsg) -> StdResult<Binary> {
+        match msg {
+            // like the registry, names nobody registered resolve to nothing
+            ArchIdQueryMsg::ResolveRecord { name } => {
+                let address = RECORDS.may_load(deps.storage, name)?;
+                let expiration = match address {
+                    Some(_) => env.block.time.plus_days(365).seconds(),
+                    None => 0,
+                };
+                to_json_binary(&ResolveRecordResponse {
+                    address,
+                    expiration,
+                })
+            }
+        }
+    }
+}
+
+fn registry() -> Box<dyn Contract<ArchwayMsg, ArchwayQuery>> {
+    Box::new(ContractWrapper::new_with_empty(
+        mock_registry::execute,
+        mock_registry::instantiate,
+        mock_registry::query,
+    ))
+}
+
+struct Suite {
+    app: PokeArchApp,
+    pokearch: PokeArchContract,
+    nft: Addr,
+    nft_code_id: u64,
+    registry: Addr,
+}
+
+impl Suite {
+    /// Deploys pokearch along with the cw721 collection it creates and a mock ArchID registry.
+    fn new() -> Self {
+        let bulbasaur = Species {
+            name: String::from("bulbasaur"),
+            types: vec![String::from("grass")],
+            image: Some(String::from("ipfs://bulbasaur")),
+            collection: None,
+            base_stats: Stats {
+                attack: 50,
+                defense: 50,
+                speed: 50,
+            },
+            required_badge: None,
+            supply: None,
+            variant_odds: None,
+            learnset: vec![],
+        };
+        let mut deployment = PokeArchBuilder::new(OWNER)
+            .with_species(bulbasaur)
+            .with_starters(&["bulbasaur"])
+            .build()
+            .unwrap();
+
+        let app = &mut deployment.app;
+        let code_id = app.store_code(registry());
+        let registry = app
+            .instantiate_contract(
+                code_id,
+                deployment.owner.clone(),
+                &Empty {},
+                &[],
+                "archid",
+                None,
+            )
+            .unwrap();
+        let msg = ExecuteMsg::SetArchId {
+            registry: registry.to_string(),
+            token: None,
+        };
+        app.execute_contract(
+            deployment.owner.clone(),
+            deployment.pokearch.addr(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+
+        Suite {
+            app: deployment.app,
+            pokearch: deployment.pokearch,
+            nft: deployment.nft,
+            nft_code_id: deployment.nft_code_id,
+            registry,
+        }
+    }
+
+    fn set_record(&mut self, name: &str, address: &str) {
+        let msg = mock_registry::SetRecord {
+            name: name.to_string(),
+            address: address.to_string(),
+        };
+        self.app
+            .execute_contract(Addr::unchecked(OWNER), self.registry.clone(), &msg, &[])
+            .unwrap();
+    }
+
+    fn execute(&mut self, sender: &str, msg: ExecuteMsg) -> Result<(), ContractError> {
+        self.app
+            .execute_contract(Addr::unchecked(sender), self.pokearch.addr(), &msg, &[])
+            .map(|_| ())
+            .map_err(|err| err.downcast().unwrap())
+    }
+
+    fn owner_of(&self, nft: &Addr, token_id: i32) -> String {
+        let msg = cw721_base::QueryMsg::<Empty>::OwnerOf {
+            token_id: token_id.to_string(),
+            include_expired: None,
+        };
+        let res: cw721::OwnerOfResponse = self.app.wrap().query_wasm_smart(nft, &msg).unwrap();
+        res.owner
+    }
+
+    fn player(&self, id: &str) -> PlayerResponse {
+        self.pokearch.player(&self.app.wrap(), id).unwrap()
+    }
+}
+
+#[test]
+fn register_requires_archid_name() {
+    let mut suite = Suite::new();
+    suite.set_record("hello.arch", ALICE);
+
+    let register = ExecuteMsg::Register {
+        id: String::from("hello.arch"),
+        starter: String::from("bulbasaur"),
+    };
+    let err = suite.execute(BOB, register.clone()).unwrap_err();
+    assert!(matches!(err, ContractError::NameNotOwned { .. }));
+
+    // names the registry doesn't know about can't be taken either
+    let err = suite
+        .execute(
+            ALICE,
+            ExecuteMsg::Register {
+                id: String::from("unknown.arch"),
+                starter: String::from("bulbasaur"),
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::NameNotOwned { .. }));
+
+    suite.execute(ALICE, register).unwrap();
+    assert_eq!(
+        Addr::unchecked(ALICE),
+        suite.player("hello.arch").player.address
+    );
+
+    // a registry that can't be queried fails the registration instead of denying the name
+    let msg = ExecuteMsg::SetArchId {
+        registry: suite.nft.to_string(),
+        token: None,
+    };
+    suite.execute(OWNER, msg).unwrap();
+    let err = suite
+        .execute(
+            ALICE,
+            ExecuteMsg::Register {
+                id: String::from("other.arch"),
+                starter: String::from("bulbasaur"),
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Std(_)));
+}
+
+#[test]
+fn reverify_after_transfer() {
+    let mut suite = Suite::new();
+    suite.set_record("hello.arch", ALICE);
+    let register = ExecuteMsg::Register {
+        id: String::from("hello.arch"),
+        starter: String::from("bulbasaur"),
+    };
+    suite.execute(ALICE, register).unwrap();
+
+    let reverify = ExecuteMsg::ReverifyPlayer {
+        id: String::from("hello.arch"),
+    };
+    let err = suite.execute(BOB, reverify.clone()).unwrap_err();
+    assert!(matches!(err, ContractError::NameNotOwned { .. }));
+
+    // the domain changes hands, so bob takes over the player
+    suite.set_record("hello.arch", BOB);
+    suite.execute(BOB, reverify.clone()).unwrap();
+    assert_eq!(
+        Addr::unchecked(BOB),
+        suite.player("hello.arch").player.address
+    );
+
+    let err = suite.execute(ALICE, reverify).unwrap_err();
+    assert!(matches!(err, ContractError::NameNotOwned { .. }));
+}
+
+#[test]
+fn repointing_collection_keeps_token_ids() {
+    let mut suite = Suite::new();
+    suite.set_record("hello.arch", ALICE);
+    suite.set_record("world.arch", BOB);
+    let register = |id: &str| ExecuteMsg::Register {
+        id: id.to_string(),
+        starter: String::from("bulbasaur"),
+    };
+    suite.execute(ALICE, register("hello.arch")).unwrap();
+
+    // pointing at the same collection again must not reuse the ids it already holds
+    let set_nft = ExecuteMsg::SetNFTContract {
+        addr: suite.nft.to_string(),
+    };
+    suite.execute(OWNER, set_nft).unwrap();
+    suite.execute(BOB, register("world.arch")).unwrap();
+
+    assert_eq!(1, suite.player("hello.arch").player.pokemons[0].token_id);
+    assert_eq!(2, suite.player("world.arch").player.pokemons[0].token_id);
+    assert_eq!(BOB, suite.owner_of(&suite.nft, 2));
+}
+
+#[test]
+fn species_mint_into_their_collection() {
+    let mut suite = Suite::new();
+    suite.set_record("hello.arch", ALICE);
+    let register = ExecuteMsg::Register {
+        id: String::from("hello.arch"),
+        starter: String::from("bulbasaur"),
+    };
+    suite.execute(ALICE, register).unwrap();
+
+    let gen2 = NewCollection {
+        id: Some(String::from("gen2")),
+        code_id: suite.nft_code_id,
+        name: "PokeArch Gen 2".to_string(),
+        symbol: "POKE2".to_string(),
+        kernel_address: "kernel".to_string(),
+    };
+    suite
+        .execute(OWNER, ExecuteMsg::CreateCollection(gen2))
+        .unwrap();
+    let collections: CollectionsResponse = suite.pokearch.collections(&suite.app.wrap()).unwrap();
+    assert_eq!(Some(suite.nft.clone()), collections.default);
+    assert_eq!("gen2", collections.collections[0].id);
+    let gen2 = collections.collections[0].address.clone();
+
+    let chikorita = Species {
+        name: String::from("chikorita"),
+        types: vec![String::from("grass")],
+        image: Some(String::from("ipfs://chikorita")),
+        collection: Some(String::from("gen2")),
+        base_stats: Stats {
+            attack: 50,
+            defense: 50,
+            speed: 50,
+        },
+        required_badge: None,
+        supply: None,
+        variant_odds: None,
+        learnset: vec![],
+    };
+    suite
+        .execute(OWNER, ExecuteMsg::SetSpecies { species: chikorita })
+        .unwrap();
+    let forest = Region {
+        name: String::from("Ilex Forest"),
+        spawns: vec![Spawn {
+            species: String::from("chikorita"),
+            weight: 1,
+            min_level: 5,
+            max_level: 5,
+            window: None,
+        }],
+        required_badge: None,
+    };
+    let msg = ExecuteMsg::SetRegion {
+        id: String::from("ilex"),
+        region: forest,
+    };
+    suite.execute(OWNER, msg).unwrap();
+    let msg = ExecuteMsg::Explore {
+        id: String::from("hello.arch"),
+        region: String::from("ilex"),
+    };
+    suite.execute(ALICE, msg).unwrap();
+    let catch = ExecuteMsg::CatchPokemon {
+        id: String::from("hello.arch"),
+        species: String::from("chikorita"),
+        health: 80,
+        curr_pokemon: 0,
+    };
+    suite.execute(ALICE, catch).unwrap();
+
+    // each collection numbers its own tokens
+    let player = suite.player("hello.arch").player;
+    assert_eq!(suite.nft, player.pokemons[0].collection);
+    assert_eq!(gen2, player.pokemons[1].collection);
+    assert_eq!(1, player.pokemons[1].token_id);
+    assert_eq!(5, player.pokemons[1].level);
+    assert_eq!(ALICE, suite.owner_of(&gen2, 1));
+}
@@ -1,7 +1,17 @@
+pub mod archid;
+pub mod battle;
 pub mod contract;
 pub mod cwfees;
 mod error;
+#[cfg(any(test, feature = "helpers"))]
+pub mod helpers;
+#[cfg(test)]
+mod integration_tests;
+pub mod metadata;
 pub mod msg;
+#[cfg(any(test, feature = "multitest"))]
+pub mod multitest;
+pub mod random;
 pub mod state;
 
 pub use crate::error::ContractError;
@@ -0,0 +1,193 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{to_json_vec, Binary, StdResult};
+
+use crate::state::{Pokemon, Species, Variant};
+
+/// ERC-721 style token metadata, generated from the stored species and pokemon.
+#[cw_serde]
+pub struct TokenMetadata {
+    pub name: String,
+    pub description: String,
+    /// The card artwork as a `data:image/svg+xml` URI.
+    pub image: String,
+    pub attributes: Vec<Trait>,
+}
+
+#[cw_serde]
+pub struct Trait {
+    pub trait_type: String,
+    pub value: String,
+}
+
+impl TokenMetadata {
+    pub fn new(species: &Species, pokemon: &Pokemon) -> Self {
+        let stats = pokemon.stats(&species.base_stats);
+        let mut attributes = vec![Trait::new("Species", &species.name)];
+        attributes.extend(species.types.iter().map(|kind| Trait::new("Type", kind)));
+        attributes.extend(
+            pokemon
+                .variant
+                .map(|variant| Trait::new("Variant", variant.as_str())),
+        );
+        attributes.push(Trait::new("Nature", pokemon.genes.nature.as_str()));
+        attributes.extend([
+            Trait::new("Level", pokemon.level),
+            Trait::new("XP", pokemon.xp),
+            Trait::new("Attack", stats.attack),
+            Trait::new("Defense", stats.defense),
+            Trait::new("Speed", stats.speed),
+            Trait::new("Original Trainer", &pokemon.original_trainer),
+        ]);
+        TokenMetadata {
+            name: format!("{} #{}", display_name(species, pokemon), pokemon.token_id),
+            description: format!(
+                "A level {} {}{} caught by {} on PokeArch.",
+                pokemon.level,
+                pokemon
+                    .variant
+                    .map(|variant| format!("{} ", variant.as_str()))
+                    .unwrap_or_default(),
+                species.name,
+                pokemon.original_trainer
+            ),
+            image: format!(
+                "data:image/svg+xml;base64,{}",
+                Binary::from(card(species, pokemon).as_bytes()).to_base64()
+            ),
+            attributes,
+        }
+    }
+
+    /// The metadata as a `data:application/json` URI, usable as a `token_uri`.
+    pub fn to_data_uri(&self) -> StdResult<String> {
+        Ok(format!(
+            "data:application/json;base64,{}",
+            Binary::from(to_json_vec(self)?).to_base64()
+        ))
+    }
+}
+
+impl Trait {
+    fn new(trait_type: &str, value: impl ToString) -> Self {
+        Trait {
+            trait_type: trait_type.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+/// Composes the SVG card of a pokemon, embedding the species artwork when there is one.
+fn card(species: &Species, pokemon: &Pokemon) -> String {
+    let stats = pokemon.stats(&species.base_stats);
+    let background = species
+        .types
+        .first()
+        .map(|kind| type_color(kind))
+        .unwrap_or(type_color("normal"));
+    let artwork = species
+        .image
+        .as_ref()
+        .map(|image| {
+            format!(
+                r#"<image href="{}" x="30" y="60" width="240" height="200"/>"#,
+                escape(image)
+            )
+        })
+        .unwrap_or_default();
+    // variants get a coloured frame on top of the type background
+    let frame = pokemon
+        .variant
+        .map(|variant| {
+            format!(
+                r#"<rect x="4" y="4" width="292" height="412" rx="14" fill="none" stroke="{}" stroke-width="8"/>"#,
+                variant_color(variant)
+            )
+        })
+        .unwrap_or_default();
+    format!(
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="300" height="420" viewBox="0 0 300 420" font-family="monospace">"#,
+            r##"<rect width="300" height="420" rx="16" fill="{background}"/>"##,
+            r##"<rect x="20" y="60" width="260" height="200" rx="8" fill="#ffffff" fill-opacity="0.4"/>"##,
+            "{frame}",
+            "{artwork}",
+            r#"<text x="20" y="40" font-size="22">{name}</text>"#,
+            r#"<text x="280" y="40" font-size="16" text-anchor="end">Lv {level}</text>"#,
+            r#"<text x="20" y="295" font-size="14">{types}</text>"#,
+            r#"<text x="20" y="330" font-size="14">ATK {attack}  DEF {defense}  SPD {speed}</text>"#,
+            r#"<text x="20" y="365" font-size="14">XP {xp}</text>"#,
+            r#"<text x="20" y="400" font-size="12">OT {trainer}</text>"#,
+            "</svg>"
+        ),
+        background = background,
+        frame = frame,
+        artwork = artwork,
+        name = escape(&display_name(species, pokemon)),
+        level = pokemon.level,
+        types = escape(&species.types.join(" / ")),
+        attack = stats.attack,
+        defense = stats.defense,
+        speed = stats.speed,
+        xp = pokemon.xp,
+        trainer = escape(&pokemon.original_trainer),
+    )
+}
+
+fn type_color(kind: &str) -> &'static str {
+    match kind {
+        "fire" => "#f08030",
+        "water" => "#6890f0",
+        "grass" => "#78c850",
+        "electric" => "#f8d030",
+        "psychic" => "#f85888",
+        "ice" => "#98d8d8",
+        "dragon" => "#7038f8",
+        "dark" => "#705848",
+        "fairy" => "#ee99ac",
+        "fighting" => "#c03028",
+        "poison" => "#a040a0",
+        "ground" => "#e0c068",
+        "flying" => "#a890f0",
+        "bug" => "#a8b820",
+        "rock" => "#b8a038",
+        "ghost" => "#705898",
+        "steel" => "#b8b8d0",
+        _ => "#a8a878",
+    }
+}
+
+fn variant_color(variant: Variant) -> &'static str {
+    match variant {
+        Variant::Shiny => "#d4af37",
+        Variant::Alternate => "#4b0082",
+    }
+}
+
+/// The species name, prefixed with the variant when the pokemon has one.
+fn display_name(species: &Species, pokemon: &Pokemon) -> String {
+    match pokemon.variant {
+        Some(variant) => format!(
+            "{} {}",
+            capitalize(variant.as_str()),
+            capitalize(&species.name)
+        ),
+        None => capitalize(&species.name),
+    }
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Player ids and species data end up inside the SVG, so they can't be trusted as markup.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -1,38 +1,336 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Coin, Decimal, Timestamp};
 
-use crate::state::Player;
+use crate::metadata::TokenMetadata;
+use crate::state::{
+    BreedingConfig, CheckInSchedule, Egg, Feature, Genes, GrantPolicy, Guild, GuildRole, Gym,
+    Items, LeaderboardKind, Move, Parent, Player, Premium, Quest, Region, RewardsLedger,
+    RewardsSplit, Species, Stats, Variant, VariantOdds,
+};
 
+/// Funds attached on instantiation fill the gas tank, which pays for fee grants. Without any,
+/// grants fail until the tank is funded or developer rewards are withdrawn into it.
 #[cw_serde]
-pub struct InstantiateMsg {}
+pub struct InstantiateMsg {
+    /// Creates a fresh collection for the game. Without it, `SetNFTContract` has to be used.
+    pub collection: Option<NewCollection>,
+}
+
+/// An andromeda cw721 collection the game instantiates with itself as the minter.
+#[cw_serde]
+pub struct NewCollection {
+    /// Registers the collection under this id. Without one, it becomes the default collection.
+    pub id: Option<String>,
+    pub code_id: u64,
+    pub name: String,
+    pub symbol: String,
+    pub kernel_address: String,
+}
 
 #[cw_serde]
 pub enum ExecuteMsg {
     RemoveAllowance(String),
+    /// Lets the address have fee grants paid from the gas tank. Owner only.
     AddAllowance(String),
+    /// Points the game at an existing collection it is allowed to mint into.
     SetNFTContract {
         addr: String,
-        token_uri: String,
     },
+    /// Instantiates a new collection and registers it once it exists.
+    CreateCollection(NewCollection),
+    /// Registers an existing collection under `id`, so species can be minted into it.
+    SetCollection {
+        id: String,
+        addr: String,
+    },
+    /// Registers a player and mints the chosen starter to the sender.
     Register {
         id: String,
+        starter: String,
     },
+    /// Mints the pokemon the player encountered, which has to be of `species`, to the sender.
     CatchPokemon {
         id: String,
-        token_uri: String,
+        species: String,
         health: i32,
         curr_pokemon: i32,
     },
-    UpdateHealth {
+    /// Uses one of the player's revives on a fainted pokemon.
+    Revive {
         id: String,
-        token_id: i32,
+        pokemon: i32,
     },
+    /// Heals the whole party. Free after the cooldown, otherwise exactly the fee has to be
+    /// attached.
+    VisitPokemonCenter {
+        id: String,
+    },
+    SetPokemonCenter {
+        cooldown: u64,
+        fee: Option<Coin>,
+    },
+    /// Pays out the reward of the current streak day. After missed days the streak starts over,
+    /// unless `protect_streak` is set and the player can pay the protection for each of them.
+    CheckIn {
+        id: String,
+        protect_streak: bool,
+    },
+    SetCheckInSchedule {
+        schedule: CheckInSchedule,
+    },
+    /// Picks a berry and walks the player's eggs a step. Possible once every three minutes.
     CollectBerries {
         id: String,
     },
+    /// Rolls a wild pokemon from the spawns of `region` that are out at this time of day, which
+    /// replaces the player's current encounter. Replacing one found less than five minutes ago
+    /// costs a ticket.
+    Explore {
+        id: String,
+        region: String,
+    },
+    SetRegion {
+        id: String,
+        region: Region,
+    },
+    /// Adds a move to the registry or replaces it.
+    SetMove {
+        id: String,
+        details: Move,
+    },
+    /// Teaches a pokemon a move from its learnset that its level allows, with full PP.
+    LearnMove {
+        id: String,
+        pokemon: i32,
+        move_id: String,
+    },
+    ForgetMove {
+        id: String,
+        pokemon: i32,
+        move_id: String,
+    },
+    /// Odds for species without their own.
+    SetVariantOdds {
+        odds: VariantOdds,
+    },
+    /// Lays an egg from two pokemon of the player sharing a type, which then have to rest for
+    /// the breeding cooldown.
+    Breed {
+        id: String,
+        first: i32,
+        second: i32,
+    },
+    /// Mints the pokemon of an egg that is ready into the player's roster.
+    HatchEgg {
+        id: String,
+        egg: u64,
+    },
+    SetBreedingConfig {
+        config: BreedingConfig,
+    },
     SetDefaultPokemon {
         id: String,
         pokemon: i32,
     },
+    /// Records the outcome of a PvP battle. The winning pokemon gains experience.
+    RecordBattle {
+        winner: String,
+        loser: String,
+        pokemon: i32,
+    },
+    /// Adds a species to the registry or replaces it.
+    SetSpecies {
+        species: Species,
+    },
+    /// Replaces the species players can choose from at registration.
+    SetStarters {
+        starters: Vec<String>,
+    },
+    /// Base URI that minted tokens point their `token_uri` at, serving the `TokenMetadata`
+    /// query as `{base_uri}/{collection}/{token_id}`. Until it's set, tokens are minted without
+    /// one and the metadata is only available through the query.
+    SetMetadataRenderer {
+        base_uri: String,
+    },
+    /// Requires player ids to be ArchID domains resolving to, or owned by, the player.
+    SetArchId {
+        registry: String,
+        token: Option<String>,
+    },
+    /// Moves a player to the sender once its ArchID domain has changed hands. Only the player
+    /// moves: the roster NFTs stay with the previous address until it transfers them.
+    ReverifyPlayer {
+        id: String,
+    },
+    /// Updates the Archway rewards metadata of this contract.
+    SetRewardsMetadata {
+        owner_address: Option<String>,
+        rewards_address: Option<String>,
+    },
+    /// Withdraws the accumulated developer rewards and splits them. Rewards paid to a rewards
+    /// address other than the contract are left to that address.
+    WithdrawRewards {},
+    SetRewardsSplit {
+        gas_tank: Decimal,
+        prize_pool: Decimal,
+        treasury: String,
+    },
+    /// Adds the attached funds to the fee grant budget. Grants are refused once it runs dry.
+    FundGasTank {},
+    /// Pays a player out of the prize pool.
+    AwardPrize {
+        id: String,
+        amount: Coin,
+    },
+    /// Sets the flat fee charged on every message to this contract. A zero amount removes it.
+    /// The rewards module charges it contract-wide, not per action, so the grant policy only
+    /// decides whether grants still cover transactions carrying premium actions.
+    /// The contract has to be the owner in its own rewards metadata for this to succeed.
+    SetPremium {
+        flat_fee: Coin,
+        grant_policy: GrantPolicy,
+    },
+    /// Stops every player action until unpaused. Owner or guardians only.
+    Pause {},
+    Unpause {},
+    /// Switches a single feature off or back on. Owner or guardians only.
+    SetFeature {
+        feature: Feature,
+        enabled: bool,
+    },
+    AddGuardian(String),
+    RemoveGuardian(String),
+    /// Closes the current leaderboard season and starts a new one.
+    ResetSeason {},
+    /// Adds a gym or replaces it.
+    SetGym {
+        id: String,
+        gym: Gym,
+    },
+    /// Battles the gym leader's team with the pokemon at the `party` indexes, in that order.
+    /// Damage taken and PP spent carry over to the roster. Beating the gym awards its badge.
+    ChallengeGym {
+        id: String,
+        gym: String,
+        party: Vec<i32>,
+    },
+    /// Founds guild `guild` with the player as its leader.
+    CreateGuild {
+        id: String,
+        guild: String,
+        name: String,
+        max_members: u32,
+    },
+    /// Invites `player` into the guild of `id`. Leader or officers only.
+    InviteToGuild {
+        id: String,
+        player: String,
+    },
+    /// Accepts an invitation to `guild`.
+    JoinGuild {
+        id: String,
+        guild: String,
+    },
+    /// Leaves the guild. A leader without other members disbands it and takes the treasury.
+    LeaveGuild {
+        id: String,
+    },
+    /// Removes `player` from the guild. Officers may only kick regular members.
+    KickFromGuild {
+        id: String,
+        player: String,
+    },
+    /// Changes the role of a member. Making someone leader hands the guild over to them and
+    /// turns the current leader into an officer. Leader only.
+    SetGuildRole {
+        id: String,
+        player: String,
+        role: GuildRole,
+    },
+    /// Moves items from the player and the attached funds into the guild treasury.
+    ContributeToGuild {
+        id: String,
+        items: Vec<Items>,
+    },
+    /// Pays items and coins out of the treasury to a member. Leader only.
+    GuildPayout {
+        id: String,
+        player: String,
+        items: Vec<Items>,
+        coins: Vec<Coin>,
+    },
+    /// Adds a quest or replaces it, keeping the progress players made on it.
+    SetQuest {
+        id: String,
+        quest: Quest,
+    },
+    /// Pays out the reward of a completed quest. Guild quests are claimed by the leader or an
+    /// officer and pay into the treasury.
+    ClaimQuest {
+        id: String,
+        quest: String,
+    },
+}
+
+impl ExecuteMsg {
+    /// Actions minting or breeding pokemon, the expensive ones the premium is meant for.
+    pub fn is_premium(&self) -> bool {
+        matches!(
+            self,
+            ExecuteMsg::Register { .. }
+                | ExecuteMsg::CatchPokemon { .. }
+                | ExecuteMsg::HatchEgg { .. }
+                | ExecuteMsg::ClaimQuest { .. }
+                | ExecuteMsg::Breed { .. }
+        )
+    }
+
+    /// Whether a global pause stops this message. Administration keeps working.
+    pub fn is_pausable(&self) -> bool {
+        matches!(
+            self,
+            ExecuteMsg::Register { .. }
+                | ExecuteMsg::CatchPokemon { .. }
+                | ExecuteMsg::CollectBerries { .. }
+                | ExecuteMsg::Explore { .. }
+                | ExecuteMsg::Breed { .. }
+                | ExecuteMsg::HatchEgg { .. }
+                | ExecuteMsg::LearnMove { .. }
+                | ExecuteMsg::ForgetMove { .. }
+                | ExecuteMsg::SetDefaultPokemon { .. }
+                | ExecuteMsg::RecordBattle { .. }
+                | ExecuteMsg::Revive { .. }
+                | ExecuteMsg::VisitPokemonCenter { .. }
+                | ExecuteMsg::CheckIn { .. }
+                | ExecuteMsg::ReverifyPlayer { .. }
+                | ExecuteMsg::ClaimQuest { .. }
+                | ExecuteMsg::ChallengeGym { .. }
+                | ExecuteMsg::CreateGuild { .. }
+                | ExecuteMsg::InviteToGuild { .. }
+                | ExecuteMsg::JoinGuild { .. }
+                | ExecuteMsg::LeaveGuild { .. }
+                | ExecuteMsg::KickFromGuild { .. }
+                | ExecuteMsg::SetGuildRole { .. }
+                | ExecuteMsg::ContributeToGuild { .. }
+                | ExecuteMsg::GuildPayout { .. }
+        )
+    }
+
+    /// The feature that has to be enabled for this message, if any.
+    pub fn feature(&self) -> Option<Feature> {
+        match self {
+            ExecuteMsg::Register { .. }
+            | ExecuteMsg::CatchPokemon { .. }
+            | ExecuteMsg::Explore { .. }
+            | ExecuteMsg::ClaimQuest { .. } => Some(Feature::Catching),
+            ExecuteMsg::RecordBattle { .. } | ExecuteMsg::ChallengeGym { .. } => {
+                Some(Feature::Battling)
+            }
+            ExecuteMsg::CollectBerries { .. } => Some(Feature::Foraging),
+            ExecuteMsg::Breed { .. } | ExecuteMsg::HatchEgg { .. } => Some(Feature::Breeding),
+            _ => None,
+        }
+    }
 }
 
 #[cw_serde]
@@ -42,9 +340,247 @@ pub enum QueryMsg {
     CheckAllowance { addr: String },
     #[returns(PlayerResponse)]
     GetPlayer { id: String },
+    #[returns(Species)]
+    GetSpecies { name: String },
+    /// How many of a species have been caught and how many are left, in total and for
+    /// `player` if given.
+    #[returns(SupplyResponse)]
+    Supply {
+        species: String,
+        player: Option<String>,
+    },
+    #[returns(VariantOdds)]
+    VariantOdds {},
+    /// Individual values and nature of a pokemon. Collection defaults to the default collection.
+    #[returns(Genes)]
+    Genes {
+        collection: Option<String>,
+        token_id: i32,
+    },
+    /// Variants caught of a species, or of all species without one.
+    #[returns(VariantCountsResponse)]
+    VariantCounts { species: Option<String> },
+    #[returns(Vec<String>)]
+    GetStarters {},
+    #[returns(CheckInSchedule)]
+    CheckInSchedule {},
+    #[returns(BreedingConfig)]
+    BreedingConfig {},
+    /// Eggs of a player and whether they are ready to hatch.
+    #[returns(Vec<EggEntry>)]
+    Eggs { id: String },
+    /// Breeding cooldown and parents of a minted pokemon. `collection` defaults to the default
+    /// collection.
+    #[returns(BreedingResponse)]
+    Breeding {
+        collection: Option<String>,
+        token_id: i32,
+    },
+    /// The default collection pokemon are minted into.
+    #[returns(Addr)]
+    GetNFTContract {},
+    #[returns(CollectionsResponse)]
+    Collections {},
+    /// Current on-chain attributes of a minted pokemon.
+    #[returns(PokemonMetadata)]
+    PokemonMetadata { collection: String, token_id: i32 },
+    /// ERC-721 style metadata generated from the pokemon's current state. `collection`
+    /// defaults to the default collection, `data_uri` also encodes it as a `token_uri`.
+    #[returns(TokenMetadataResponse)]
+    TokenMetadata {
+        collection: Option<String>,
+        token_id: i32,
+        data_uri: Option<bool>,
+    },
+    /// Rewards accumulated for this contract that haven't been withdrawn yet.
+    #[returns(PendingRewardsResponse)]
+    PendingRewards {},
+    #[returns(RewardsResponse)]
+    Rewards {},
+    #[returns(Premium)]
+    Premium {},
+    #[returns(PauseStatusResponse)]
+    PauseStatus {},
+    #[returns(Gym)]
+    Gym { id: String },
+    #[returns(Vec<GymEntry>)]
+    Gyms {},
+    #[returns(Region)]
+    Region { id: String },
+    #[returns(Vec<RegionEntry>)]
+    Regions {},
+    #[returns(Move)]
+    Move { id: String },
+    #[returns(Vec<MoveEntry>)]
+    Moves {},
+    #[returns(GuildResponse)]
+    Guild { guild: String },
+    /// The guild a player belongs to, if any.
+    #[returns(Option<String>)]
+    PlayerGuild { id: String },
+    /// Ranked guilds of a board, highest score first. Entries hold guild ids.
+    #[returns(LeaderboardResponse)]
+    GuildLeaderboard {
+        board: LeaderboardKind,
+        season: Option<u32>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(Vec<QuestEntry>)]
+    ActiveQuests {},
+    /// Progress of a player on every active quest and those it completed. Guild quests show the
+    /// progress of the player's guild.
+    #[returns(Vec<QuestProgressEntry>)]
+    QuestProgress { id: String },
+    /// Ranked players of a board, highest score first. `season` defaults to the current one.
+    #[returns(LeaderboardResponse)]
+    Leaderboard {
+        board: LeaderboardKind,
+        season: Option<u32>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 #[cw_serde]
 pub struct PlayerResponse {
     pub player: Player,
 }
+
+#[cw_serde]
+pub struct LeaderboardEntry {
+    pub player: String,
+    pub score: i32,
+}
+
+#[cw_serde]
+pub struct LeaderboardResponse {
+    pub season: u32,
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+#[cw_serde]
+pub struct PendingRewardsResponse {
+    pub records: u64,
+    pub rewards: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct RewardsResponse {
+    pub split: RewardsSplit,
+    pub ledger: RewardsLedger,
+    pub gas_tank: Vec<Coin>,
+    pub prize_pool: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct PauseStatusResponse {
+    pub paused: bool,
+    pub disabled: Vec<Feature>,
+}
+
+#[cw_serde]
+pub struct CollectionEntry {
+    pub id: String,
+    pub address: Addr,
+}
+
+#[cw_serde]
+pub struct CollectionsResponse {
+    pub default: Option<Addr>,
+    pub collections: Vec<CollectionEntry>,
+}
+
+#[cw_serde]
+pub struct GymEntry {
+    pub id: String,
+    pub gym: Gym,
+}
+
+#[cw_serde]
+pub struct SupplyResponse {
+    pub caught: u32,
+    /// Unset for species without a supply.
+    pub remaining: Option<u32>,
+    /// Unset without a player or a per-player cap.
+    pub player_remaining: Option<u32>,
+}
+
+#[cw_serde]
+pub struct VariantCountsResponse {
+    pub shiny: u32,
+    pub alternate: u32,
+}
+
+#[cw_serde]
+pub struct RegionEntry {
+    pub id: String,
+    pub region: Region,
+}
+
+#[cw_serde]
+pub struct MoveEntry {
+    pub id: String,
+    pub details: Move,
+}
+
+#[cw_serde]
+pub struct EggEntry {
+    pub id: u64,
+    pub egg: Egg,
+    pub ready: bool,
+}
+
+#[cw_serde]
+pub struct BreedingResponse {
+    /// When the pokemon may breed again, unset if it never has.
+    pub ready_at: Option<Timestamp>,
+    /// Parents of the pokemon, empty unless it hatched from an egg.
+    pub parents: Vec<Parent>,
+}
+
+#[cw_serde]
+pub struct GuildMember {
+    pub player: String,
+    pub role: GuildRole,
+}
+
+#[cw_serde]
+pub struct GuildResponse {
+    pub guild: Guild,
+    pub members: Vec<GuildMember>,
+}
+
+#[cw_serde]
+pub struct QuestEntry {
+    pub id: String,
+    pub quest: Quest,
+}
+
+#[cw_serde]
+pub struct QuestProgressEntry {
+    pub quest: String,
+    pub progress: u32,
+    pub goal: u32,
+    pub claimed: bool,
+}
+
+#[cw_serde]
+pub struct PokemonMetadata {
+    pub species: String,
+    pub types: Vec<String>,
+    pub level: i32,
+    pub xp: i32,
+    pub stats: Stats,
+    pub original_trainer: String,
+    pub variant: Option<Variant>,
+    /// Player whose roster currently holds the pokemon.
+    pub trainer: String,
+}
+
+#[cw_serde]
+pub struct TokenMetadataResponse {
+    pub metadata: TokenMetadata,
+    /// The metadata as a base64 `data:application/json` URI, when requested.
+    pub token_uri: Option<String>,
+}
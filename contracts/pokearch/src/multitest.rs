@@ -0,0 +1,157 @@
+use archway_bindings::{ArchwayMsg, ArchwayQuery};
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::{Addr, Empty};
+use cw_multi_test::error::AnyResult;
+use cw_multi_test::{
+    App, BankKeeper, BasicAppBuilder, Contract, ContractWrapper, DistributionKeeper, Executor,
+    FailingModule, GovFailingModule, IbcFailingModule, StakeKeeper, StargateAccepting, WasmKeeper,
+};
+
+use crate::helpers::PokeArchContract;
+use crate::msg::{ExecuteMsg, InstantiateMsg, NewCollection};
+use crate::state::Species;
+
+/// An app speaking Archway's custom messages and queries, which accepts the fee grant
+/// registration sent at instantiation.
+pub type PokeArchApp = App<
+    BankKeeper,
+    MockApi,
+    MockStorage,
+    FailingModule<ArchwayMsg, ArchwayQuery, Empty>,
+    WasmKeeper<ArchwayMsg, ArchwayQuery>,
+    StakeKeeper,
+    DistributionKeeper,
+    IbcFailingModule,
+    GovFailingModule,
+    StargateAccepting,
+>;
+
+/// cw721-base behind the andromeda instantiate message the game sends when creating collections.
+mod collection {
+    use andromeda_non_fungible_tokens::cw721::InstantiateMsg as AndrInstantiateMsg;
+    use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, StdResult};
+
+    pub fn instantiate(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: AndrInstantiateMsg,
+    ) -> StdResult<Response> {
+        let msg = cw721_base::InstantiateMsg {
+            name: msg.name,
+            symbol: msg.symbol,
+            minter: msg.minter.into_string(),
+        };
+        cw721_base::entry::instantiate(deps, env, info, msg)
+    }
+}
+
+pub fn pokearch_contract() -> Box<dyn Contract<ArchwayMsg, ArchwayQuery>> {
+    Box::new(
+        ContractWrapper::new(
+            crate::contract::execute,
+            crate::contract::instantiate,
+            crate::contract::query,
+        )
+        .with_sudo(crate::contract::sudo)
+        .with_reply(crate::contract::reply),
+    )
+}
+
+/// A cw721 collection that pokearch can instantiate and mint into.
+pub fn cw721_contract() -> Box<dyn Contract<ArchwayMsg, ArchwayQuery>> {
+    Box::new(ContractWrapper::new_with_empty(
+        cw721_base::entry::execute,
+        collection::instantiate,
+        cw721_base::entry::query,
+    ))
+}
+
+pub fn mock_app() -> PokeArchApp {
+    BasicAppBuilder::<ArchwayMsg, ArchwayQuery>::new_custom()
+        .with_stargate(StargateAccepting)
+        .build(|_, _, _| {})
+}
+
+/// Deploys pokearch together with the cw721 collection it creates for itself.
+pub struct PokeArchBuilder {
+    owner: Addr,
+    species: Vec<Species>,
+    starters: Vec<String>,
+}
+
+impl PokeArchBuilder {
+    pub fn new(owner: impl Into<String>) -> Self {
+        PokeArchBuilder {
+            owner: Addr::unchecked(owner),
+            species: vec![],
+            starters: vec![],
+        }
+    }
+
+    /// Registers `species` after instantiation.
+    pub fn with_species(mut self, species: Species) -> Self {
+        self.species.push(species);
+        self
+    }
+
+    pub fn with_starters(mut self, starters: &[&str]) -> Self {
+        self.starters = starters.iter().map(|starter| starter.to_string()).collect();
+        self
+    }
+
+    pub fn build(self) -> AnyResult<Deployment> {
+        self.build_in(mock_app())
+    }
+
+    /// Deploys into an existing app, e.g. one with other contracts or balances set up.
+    pub fn build_in(self, mut app: PokeArchApp) -> AnyResult<Deployment> {
+        let nft_code_id = app.store_code(cw721_contract());
+        let code_id = app.store_code(pokearch_contract());
+        let msg = InstantiateMsg {
+            collection: Some(NewCollection {
+                id: None,
+                code_id: nft_code_id,
+                name: "PokeArch".to_string(),
+                symbol: "POKE".to_string(),
+                kernel_address: "kernel".to_string(),
+            }),
+        };
+        let addr =
+            app.instantiate_contract(code_id, self.owner.clone(), &msg, &[], "pokearch", None)?;
+        let pokearch = PokeArchContract(addr);
+        let nft = pokearch.nft_contract(&app.wrap())?;
+
+        let mut messages: Vec<_> = self
+            .species
+            .into_iter()
+            .map(|species| ExecuteMsg::SetSpecies { species })
+            .collect();
+        if !self.starters.is_empty() {
+            messages.push(ExecuteMsg::SetStarters {
+                starters: self.starters,
+            });
+        }
+        for msg in messages {
+            app.execute_contract(self.owner.clone(), pokearch.addr(), &msg, &[])?;
+        }
+
+        Ok(Deployment {
+            app,
+            owner: self.owner,
+            pokearch,
+            nft,
+            nft_code_id,
+        })
+    }
+}
+
+pub struct Deployment {
+    pub app: PokeArchApp,
+    pub owner: Addr,
+    pub pokearch: PokeArchContract,
+    /// The default collection, created by pokearch at instantiation.
+    pub nft: Addr,
+    /// Code of the cw721 collection, for creating more of them.
+    pub nft_code_id: u64,
+}
@@ -0,0 +1,61 @@
+use cosmwasm_std::Env;
+use sha2::{Digest, Sha256};
+
+/// Randomness derived from the block and whatever the roll is for. Anyone can work it out once
+/// the block is known, so outcomes worth retrying for are rolled once and stored, like the
+/// encounter of an exploration, instead of on the message that claims them.
+pub struct Rng {
+    seed: [u8; 32],
+    rolls: u64,
+}
+
+impl Rng {
+    /// Seeds from the block and transaction along with `salt`, e.g. the player and region of an
+    /// encounter.
+    pub fn new(env: &Env, salt: &[&[u8]]) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(env.block.height.to_be_bytes());
+        hasher.update(env.block.time.nanos().to_be_bytes());
+        if let Some(tx) = &env.transaction {
+            hasher.update(tx.index.to_be_bytes());
+        }
+        for part in salt {
+            hasher.update((part.len() as u64).to_be_bytes());
+            hasher.update(part);
+        }
+        Rng {
+            seed: hasher.finalize().into(),
+            rolls: 0,
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let hash = Sha256::new()
+            .chain_update(self.seed)
+            .chain_update(self.rolls.to_be_bytes())
+            .finalize();
+        self.rolls += 1;
+        u64::from_be_bytes(hash[..8].try_into().unwrap())
+    }
+
+    /// A number in `0..bound`, or 0 for an empty range.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        match bound {
+            0 => 0,
+            _ => self.next_u64() % bound,
+        }
+    }
+
+    /// Picks one of `items` with odds proportional to its weight, if any has weight.
+    pub fn weighted<'a, T>(&mut self, items: &'a [T], weight: impl Fn(&T) -> u64) -> Option<&'a T> {
+        let total = items.iter().map(&weight).sum();
+        let mut roll = self.below(total);
+        for item in items {
+            match roll.checked_sub(weight(item)) {
+                Some(rest) => roll = rest,
+                None => return Some(item),
+            }
+        }
+        None
+    }
+}
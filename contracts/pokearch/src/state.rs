@@ -1,31 +1,833 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Empty};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, Coin, Decimal, Empty, Order, StdResult, Storage, Timestamp};
+use cw_storage_plus::{Bound, Item, Map};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct Pokemon {
+    /// Collection the token was minted into.
+    pub collection: Addr,
     pub token_id: i32,
+    pub species: String,
     pub index: i32,
     pub health: i32,
+    pub level: i32,
+    pub xp: i32,
+    /// Id of the player the pokemon was minted to.
+    pub original_trainer: String,
+    /// Rare look rolled when the pokemon was caught.
+    pub variant: Option<Variant>,
+    /// Fixed when the pokemon is minted.
+    pub genes: Genes,
+    /// Up to four moves from the learnset of the species.
+    pub moves: Vec<KnownMove>,
+}
+
+impl Pokemon {
+    pub fn is_fainted(&self) -> bool {
+        self.health <= 0
+    }
+
+    /// Stats of the pokemon at its level, given the base stats of its species.
+    pub fn stats(&self, base: &Stats) -> Stats {
+        self.genes.apply(base, self.level)
+    }
+}
+
+/// What sets a pokemon apart from others of its species.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Genes {
+    /// Individual values, each worth half a base stat point.
+    pub ivs: Stats,
+    pub nature: Nature,
+}
+
+impl Genes {
+    pub fn apply(&self, base: &Stats, level: i32) -> Stats {
+        let stats = Stats {
+            attack: base.attack + self.ivs.attack / 2,
+            defense: base.defense + self.ivs.defense / 2,
+            speed: base.speed + self.ivs.speed / 2,
+        };
+        self.nature.apply(stats.at_level(level))
+    }
+}
+
+/// Raises one stat by a tenth at the expense of another, apart from hardy which is neutral.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Nature {
+    Hardy,
+    Lonely,
+    Brave,
+    Bold,
+    Relaxed,
+    Timid,
+    Hasty,
+}
+
+impl Nature {
+    pub const ALL: [Nature; 7] = [
+        Nature::Hardy,
+        Nature::Lonely,
+        Nature::Brave,
+        Nature::Bold,
+        Nature::Relaxed,
+        Nature::Timid,
+        Nature::Hasty,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Nature::Hardy => "hardy",
+            Nature::Lonely => "lonely",
+            Nature::Brave => "brave",
+            Nature::Bold => "bold",
+            Nature::Relaxed => "relaxed",
+            Nature::Timid => "timid",
+            Nature::Hasty => "hasty",
+        }
+    }
+
+    pub fn apply(&self, stats: Stats) -> Stats {
+        // percentages for attack, defense and speed
+        let [attack, defense, speed] = match self {
+            Nature::Hardy => [100, 100, 100],
+            Nature::Lonely => [110, 90, 100],
+            Nature::Brave => [110, 100, 90],
+            Nature::Bold => [90, 110, 100],
+            Nature::Relaxed => [100, 110, 90],
+            Nature::Timid => [90, 100, 110],
+            Nature::Hasty => [100, 90, 110],
+        };
+        Stats {
+            attack: stats.attack * attack / 100,
+            defense: stats.defense * defense / 100,
+            speed: stats.speed * speed / 100,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct Player {
     pub id: String,
+    pub address: Addr,
     pub potions: i32,
     pub berries: i32,
     pub default_pokemon: i32,
     pub pokemons: Vec<Pokemon>,
+    pub wins: i32,
+    pub revives: i32,
+    /// Rare-encounter tickets, spent to explore again without waiting out the cooldown.
+    pub tickets: i32,
+    pub last_center_visit: Option<Timestamp>,
+    pub last_forage: Option<Timestamp>,
+    /// Consecutive days the player checked in, including the last one.
+    pub streak: u32,
+    pub last_check_in: Option<Timestamp>,
+    /// Badges of the gyms the player has beaten.
+    pub badges: Vec<String>,
+    /// Wild pokemon met while exploring, the only one the player can catch.
+    pub encounter: Option<Encounter>,
+}
+
+impl Player {
+    pub fn items_mut(&mut self, item: ItemKind) -> &mut i32 {
+        match item {
+            ItemKind::Potion => &mut self.potions,
+            ItemKind::Berry => &mut self.berries,
+            ItemKind::Revive => &mut self.revives,
+            ItemKind::Ticket => &mut self.tickets,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    Potion,
+    Berry,
+    Revive,
+    Ticket,
+}
+
+impl ItemKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ItemKind::Potion => "potion",
+            ItemKind::Berry => "berry",
+            ItemKind::Revive => "revive",
+            ItemKind::Ticket => "ticket",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Items {
+    pub kind: ItemKind,
+    pub amount: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Species {
+    pub name: String,
+    pub types: Vec<String>,
+    /// Artwork embedded in the generated token card, if any.
+    pub image: Option<String>,
+    /// Id of the collection this species is minted into. Unset means the default collection.
+    pub collection: Option<String>,
+    pub base_stats: Stats,
+    /// Badge a player needs before catching this species.
+    pub required_badge: Option<String>,
+    /// Limits on how many can be caught. Unset means unlimited.
+    pub supply: Option<Supply>,
+    /// Replaces the global variant odds for this species.
+    pub variant_odds: Option<VariantOdds>,
+    /// Moves the species can learn, with the level each becomes available at.
+    pub learnset: Vec<LearnableMove>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Variant {
+    Shiny,
+    /// An alternate form of the species.
+    Alternate,
+}
+
+impl Variant {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Variant::Shiny => "shiny",
+            Variant::Alternate => "alternate",
+        }
+    }
+}
+
+/// Odds of catching each variant as one in so many catches. Zero never rolls it.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+pub struct VariantOdds {
+    pub shiny: u32,
+    pub alternate: u32,
+}
+
+/// A cap on how many pokemon of a species can ever be caught, for legendaries and events.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Supply {
+    pub max: u32,
+    /// Catches each player is allowed.
+    pub per_player: Option<u32>,
+    /// The species only spawns from `start` until `end`. Unset ends leave it open.
+    pub start: Option<Timestamp>,
+    pub end: Option<Timestamp>,
+}
+
+impl Supply {
+    pub fn is_spawning(&self, time: Timestamp) -> bool {
+        self.start.is_none_or(|start| time >= start) && self.end.is_none_or(|end| time < end)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Stats {
+    pub attack: i32,
+    pub defense: i32,
+    pub speed: i32,
+}
+
+impl Stats {
+    /// Base stats grown to `level`, doubling by level 51.
+    pub fn at_level(&self, level: i32) -> Stats {
+        let grow = |base: i32| base + base * (level - 1) / 50;
+        Stats {
+            attack: grow(self.attack),
+            defense: grow(self.defense),
+            speed: grow(self.speed),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ArchId {
+    /// ArchID registry that player ids are resolved against.
+    pub registry: Addr,
+    /// ArchID domain collection, used to accept domain owners as well as resolved addresses.
+    pub token: Option<Addr>,
+}
+
+/// How withdrawn developer rewards are divided. The treasury receives whatever the gas tank and
+/// the prize pool don't, so rounding never leaves coins unaccounted for.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RewardsSplit {
+    pub gas_tank: Decimal,
+    pub prize_pool: Decimal,
+    pub treasury: Addr,
+}
+
+/// Totals of all rewards withdrawn so far and where they went.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct RewardsLedger {
+    pub withdrawn: Vec<Coin>,
+    pub gas_tank: Vec<Coin>,
+    pub prize_pool: Vec<Coin>,
+    pub treasury: Vec<Coin>,
+}
+
+/// How fee grants treat transactions that carry premium actions.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GrantPolicy {
+    /// The gas tank pays the premium along with the gas.
+    Sponsor,
+    /// Transactions with premium actions aren't granted, players pay for them themselves.
+    /// Every other message to the contract stays sponsored, flat fee included.
+    Exclude,
+}
+
+impl GrantPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GrantPolicy::Sponsor => "sponsor",
+            GrantPolicy::Exclude => "exclude",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Premium {
+    /// Flat fee the rewards module charges on top of gas for every message to this contract,
+    /// whichever action it carries.
+    pub flat_fee: Option<Coin>,
+    pub grant_policy: GrantPolicy,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PokemonCenter {
+    /// Seconds a player has to wait between free visits.
+    pub cooldown: u64,
+    /// Price of a visit during the cooldown. Without a fee, players have to wait.
+    pub fee: Option<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct CheckInSchedule {
+    /// Reward for each day of a streak. Streaks longer than the schedule keep getting the last.
+    pub rewards: Vec<Items>,
+    /// Spent for every missed day to keep a streak alive. Without it, streaks can't be kept.
+    pub protection: Option<Items>,
 }
 
 pub const OWNER: Item<Addr> = Item::new("owner");
 
 pub const ALLOWED_ADDRESSES: Map<Addr, Empty> = Map::new("allowed_addresses");
 
+/// Addresses that may pause the game alongside the owner.
+pub const GUARDIANS: Map<Addr, Empty> = Map::new("guardians");
+
+/// Stops every player action while set.
+pub const PAUSED: Item<bool> = Item::new("paused");
+
+pub const DISABLED_FEATURES: Item<Vec<Feature>> = Item::new("disabled_features");
+
 pub const NFT_CONTRACT: Item<Addr> = Item::new("nft_contract");
 
 pub const PLAYERS: Map<String, Player> = Map::new("players");
 
-pub const TOKEN: Item<i32> = Item::new("token");
+/// Named collections, e.g. one per generation, next to the default `NFT_CONTRACT`.
+pub const COLLECTIONS: Map<String, Addr> = Map::new("collections");
+
+/// Id of the collection being instantiated, `None` when it's going to be the default one.
+pub const PENDING_COLLECTION: Item<Option<String>> = Item::new("pending_collection");
+
+/// Last token id minted into each collection.
+pub const TOKENS: Map<Addr, i32> = Map::new("tokens");
+
+/// Player whose roster holds each minted token, by collection and token id.
+pub const TOKEN_PLAYERS: Map<(Addr, i32), String> = Map::new("token_players");
+
+/// A pokemon waiting for its mint to be confirmed before it joins the player's roster.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PendingMint {
+    pub player: String,
+    pub pokemon: Pokemon,
+    /// Whether the mint counts as a catch for leaderboards and quests.
+    pub caught: bool,
+}
+
+pub const PENDING_MINT: Item<PendingMint> = Item::new("pending_mint");
+
+pub const POKEMON_CENTER: Item<PokemonCenter> = Item::new("pokemon_center");
+
+pub const CHECK_IN: Item<CheckInSchedule> = Item::new("check_in");
+
+/// Hours of the day, in UTC, from `start` up to `end`. Windows with `start` after `end` wrap
+/// past midnight.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+pub struct TimeWindow {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl TimeWindow {
+    pub fn contains(&self, hour: u32) -> bool {
+        match self.start <= self.end {
+            true => self.start <= hour && hour < self.end,
+            false => self.start <= hour || hour < self.end,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Spawn {
+    pub species: String,
+    /// Odds of this spawn relative to the others available at the time.
+    pub weight: u32,
+    pub min_level: i32,
+    pub max_level: i32,
+    /// Hours the species shows up in. Without one it's around all day.
+    pub window: Option<TimeWindow>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Region {
+    pub name: String,
+    pub spawns: Vec<Spawn>,
+    /// Badge a player needs to explore the region.
+    pub required_badge: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Encounter {
+    pub region: String,
+    pub species: String,
+    pub level: i32,
+    /// Rolled when the pokemon is found, like its genes, so retrying the catch can't change it.
+    pub variant: Option<Variant>,
+    pub genes: Genes,
+    /// Exploring again before the cooldown has passed costs a ticket.
+    pub found_at: Timestamp,
+}
+
+pub const REGIONS: Map<String, Region> = Map::new("regions");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Move {
+    /// Type of the move, such as `fire`.
+    pub kind: String,
+    pub power: u32,
+    /// Chance to hit, in percent.
+    pub accuracy: u32,
+    /// Uses before it has to be replenished at a pokemon center.
+    pub pp: u32,
+    pub effect: Option<MoveEffect>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MoveEffect {
+    /// Restores health of the user.
+    Heal { amount: i32 },
+    /// Hurts the user as well.
+    Recoil { amount: i32 },
+    /// Keeps the target from striking back on its next turn.
+    Flinch {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct LearnableMove {
+    pub level: i32,
+    /// Id of the move in the registry.
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct KnownMove {
+    pub id: String,
+    /// Uses left until the next pokemon center visit.
+    pub pp: u32,
+}
+
+pub const MOVES: Map<String, Move> = Map::new("moves");
+
+/// Pokemon caught of each species, counting quest rewards and hatched eggs.
+pub const SPECIES_CAUGHT: Map<String, u32> = Map::new("species_caught");
+
+/// Pokemon each player caught of a species, by species and player.
+pub const PLAYER_CAUGHT: Map<(String, String), u32> = Map::new("player_caught");
+
+pub const VARIANT_ODDS: Item<VariantOdds> = Item::new("variant_odds");
+
+/// Variants caught so far, by variant and species.
+pub const VARIANTS_CAUGHT: Map<(String, String), u32> = Map::new("variants_caught");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct BreedingConfig {
+    /// Seconds a pokemon has to rest after producing an egg.
+    pub cooldown: u64,
+    /// Blocks after which an egg hatches.
+    pub hatch_blocks: u64,
+    /// Steps that hatch an egg before that. Every berry collected is a step.
+    pub hatch_steps: u32,
+}
+
+/// A minted pokemon that produced an egg.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Parent {
+    pub collection: Addr,
+    pub token_id: i32,
+    pub species: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Egg {
+    /// Species the egg hatches into, inherited from the first parent.
+    pub species: String,
+    pub parents: Vec<Parent>,
+    /// Height of the block the egg was laid at.
+    pub laid_at: u64,
+    pub steps: u32,
+    /// Inherited from the parents when the egg is laid.
+    pub genes: Genes,
+}
+
+impl Egg {
+    pub fn is_ready(&self, config: &BreedingConfig, height: u64) -> bool {
+        height >= self.laid_at + config.hatch_blocks || self.steps >= config.hatch_steps
+    }
+}
+
+pub const BREEDING: Item<BreedingConfig> = Item::new("breeding");
+
+/// Eggs waiting to hatch, by player and egg id.
+pub const EGGS: Map<(String, u64), Egg> = Map::new("eggs");
+
+/// Number of eggs laid so far, used for egg ids.
+pub const EGG_COUNT: Item<u64> = Item::new("egg_count");
+
+/// When each minted pokemon may breed again, by collection and token id.
+pub const BREEDING_COOLDOWNS: Map<(Addr, i32), Timestamp> = Map::new("breeding_cooldowns");
+
+/// Parents of every pokemon hatched from an egg, by collection and token id.
+pub const LINEAGE: Map<(Addr, i32), Vec<Parent>> = Map::new("lineage");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct GymPokemon {
+    pub species: String,
+    pub level: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Gym {
+    pub leader: String,
+    /// Sent out in order, each one staying in until it faints.
+    pub team: Vec<GymPokemon>,
+    /// Level every pokemon of a challenging party needs.
+    pub min_level: i32,
+    /// Badge a player needs before challenging this gym.
+    pub required_badge: Option<String>,
+    /// Awarded to players who beat the gym.
+    pub badge: String,
+}
+
+pub const GYMS: Map<String, Gym> = Map::new("gyms");
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GuildRole {
+    /// Runs the guild and its treasury. There is exactly one.
+    Leader,
+    /// Invites and kicks regular members.
+    Officer,
+    Member,
+}
+
+impl GuildRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GuildRole::Leader => "leader",
+            GuildRole::Officer => "officer",
+            GuildRole::Member => "member",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct Treasury {
+    pub items: Vec<Items>,
+    pub coins: Vec<Coin>,
+}
+
+impl Treasury {
+    pub fn add_items(&mut self, items: Items) {
+        match self.items.iter_mut().find(|held| held.kind == items.kind) {
+            Some(held) => held.amount += items.amount,
+            None => self.items.push(items),
+        }
+    }
+
+    /// Takes `items` out of the treasury, returning false when it doesn't hold enough.
+    pub fn take_items(&mut self, items: Items) -> bool {
+        match self.items.iter_mut().find(|held| held.kind == items.kind) {
+            Some(held) if held.amount >= items.amount => {
+                held.amount -= items.amount;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Guild {
+    pub name: String,
+    /// Id of the leading player.
+    pub leader: String,
+    pub max_members: u32,
+    pub members: u32,
+    pub treasury: Treasury,
+}
+
+pub const GUILDS: Map<String, Guild> = Map::new("guilds");
+
+/// Role of each member, by guild and player id.
+pub const GUILD_MEMBERS: Map<(String, String), GuildRole> = Map::new("guild_members");
+
+/// Guild each player belongs to.
+pub const PLAYER_GUILDS: Map<String, String> = Map::new("player_guilds");
+
+/// Open invitations, by guild and player id.
+pub const GUILD_INVITES: Map<(String, String), Empty> = Map::new("guild_invites");
+
+pub const SPECIES: Map<String, Species> = Map::new("species");
+
+pub const REWARDS_SPLIT: Item<RewardsSplit> = Item::new("rewards_split");
+
+pub const REWARDS_LEDGER: Item<RewardsLedger> = Item::new("rewards_ledger");
+
+/// Balance set aside to pay for fee grants.
+pub const GAS_TANK: Item<Vec<Coin>> = Item::new("gas_tank");
+
+/// Balance set aside to award prizes to players.
+pub const PRIZE_POOL: Item<Vec<Coin>> = Item::new("prize_pool");
+
+pub const PREMIUM: Item<Premium> = Item::new("premium");
+
+/// When set, player ids have to be ArchID domains controlled by the player.
+pub const ARCHID: Item<ArchId> = Item::new("archid");
+
+/// Base URI of a renderer serving the `TokenMetadata` query. Minted tokens point at
+/// `{base}/{collection}/{token_id}`, so their metadata follows the pokemon as it changes.
+pub const METADATA_RENDERER: Item<String> = Item::new("metadata_renderer");
+
+/// Species a new player can pick from at registration.
+pub const STARTERS: Item<Vec<String>> = Item::new("starters");
+
+pub const SEASON: Item<u32> = Item::new("season");
+
+/// What a player has to do to complete a quest.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Objective {
+    /// Catch `count` pokemon, only counting those of type `kind` when it's set.
+    Catch {
+        count: u32,
+        kind: Option<String>,
+    },
+    CollectBerries {
+        count: u32,
+    },
+    WinBattles {
+        count: u32,
+    },
+}
+
+impl Objective {
+    pub fn count(&self) -> u32 {
+        match self {
+            Objective::Catch { count, .. }
+            | Objective::CollectBerries { count }
+            | Objective::WinBattles { count } => *count,
+        }
+    }
+}
+
+/// Paid out when a completed quest is claimed. Experience goes to the default pokemon.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct QuestReward {
+    pub potions: i32,
+    pub berries: i32,
+    pub revives: i32,
+    pub xp: i32,
+    /// Species of a pokemon minted to the player.
+    pub pokemon: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Quest {
+    pub objective: Objective,
+    pub reward: QuestReward,
+    /// Only active quests make progress. Completed ones can still be claimed.
+    pub active: bool,
+    /// Guild quests count the progress of all members together and pay into the treasury.
+    pub guild: bool,
+}
+
+impl Quest {
+    /// Where progress on quest `id` is kept for a player in `guild`, if they can make any.
+    pub fn progress_key(
+        &self,
+        id: &str,
+        player: &str,
+        guild: Option<&str>,
+    ) -> Option<(QuestProgressMap, (String, String))> {
+        match (self.guild, guild) {
+            (false, _) => Some((QUEST_PROGRESS, (player.to_string(), id.to_string()))),
+            (true, Some(guild)) => {
+                Some((GUILD_QUEST_PROGRESS, (guild.to_string(), id.to_string())))
+            }
+            (true, None) => None,
+        }
+    }
+}
+
+pub type QuestProgressMap = Map<'static, (String, String), QuestProgress>;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct QuestProgress {
+    pub progress: u32,
+    pub claimed: bool,
+}
+
+pub const QUESTS: Map<String, Quest> = Map::new("quests");
+
+/// Progress of each guild on each guild quest, by guild and quest id.
+pub const GUILD_QUEST_PROGRESS: QuestProgressMap = Map::new("guild_quest_progress");
+
+/// Progress of each player on each quest, by player and quest id.
+pub const QUEST_PROGRESS: QuestProgressMap = Map::new("quest_progress");
+
+/// Parts of the game that can be switched off on their own.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    /// Catching pokemon, minting starters and claiming quests, which can reward pokemon.
+    Catching,
+    Battling,
+    Foraging,
+    Breeding,
+    FeeGrants,
+}
+
+impl Feature {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Feature::Catching => "catching",
+            Feature::Battling => "battling",
+            Feature::Foraging => "foraging",
+            Feature::Breeding => "breeding",
+            Feature::FeeGrants => "fee_grants",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardKind {
+    Catches,
+    Level,
+    Berries,
+    Wins,
+}
+
+impl LeaderboardKind {
+    pub const ALL: [LeaderboardKind; 4] = [
+        LeaderboardKind::Catches,
+        LeaderboardKind::Level,
+        LeaderboardKind::Berries,
+        LeaderboardKind::Wins,
+    ];
+
+    pub fn board(&self) -> Leaderboard<'static> {
+        match self {
+            LeaderboardKind::Catches => Leaderboard::new("lb_catches", "lb_catches_rank"),
+            LeaderboardKind::Level => Leaderboard::new("lb_level", "lb_level_rank"),
+            LeaderboardKind::Berries => Leaderboard::new("lb_berries", "lb_berries_rank"),
+            LeaderboardKind::Wins => Leaderboard::new("lb_wins", "lb_wins_rank"),
+        }
+    }
+
+    /// The board ranking guilds by what their members did while in them. Guild levels are
+    /// the levels members gained.
+    pub fn guild_board(&self) -> Leaderboard<'static> {
+        match self {
+            LeaderboardKind::Catches => Leaderboard::new("glb_catches", "glb_catches_rank"),
+            LeaderboardKind::Level => Leaderboard::new("glb_level", "glb_level_rank"),
+            LeaderboardKind::Berries => Leaderboard::new("glb_berries", "glb_berries_rank"),
+            LeaderboardKind::Wins => Leaderboard::new("glb_wins", "glb_wins_rank"),
+        }
+    }
+}
+
+/// A per-season leaderboard. `scores` holds the current score of every ranked player and
+/// `ranking` mirrors it keyed by score, so the top of the board is a descending range scan.
+/// Seasons are part of both keys, which leaves the standings of past seasons untouched.
+pub struct Leaderboard<'a> {
+    pub scores: Map<'a, (u32, String), i32>,
+    pub ranking: Map<'a, (u32, i32, String), Empty>,
+}
+
+impl<'a> Leaderboard<'a> {
+    pub const fn new(scores_namespace: &'a str, ranking_namespace: &'a str) -> Self {
+        Leaderboard {
+            scores: Map::new(scores_namespace),
+            ranking: Map::new(ranking_namespace),
+        }
+    }
+
+    pub fn score(&self, storage: &dyn Storage, season: u32, id: &str) -> StdResult<Option<i32>> {
+        self.scores.may_load(storage, (season, id.to_string()))
+    }
+
+    pub fn set_score(
+        &self,
+        storage: &mut dyn Storage,
+        season: u32,
+        id: &str,
+        score: i32,
+    ) -> StdResult<()> {
+        if let Some(old) = self.score(storage, season, id)? {
+            self.ranking.remove(storage, (season, old, id.to_string()));
+        }
+        self.scores
+            .save(storage, (season, id.to_string()), &score)?;
+        self.ranking
+            .save(storage, (season, score, id.to_string()), &Empty {})
+    }
+
+    pub fn remove(&self, storage: &mut dyn Storage, season: u32, id: &str) -> StdResult<()> {
+        if let Some(old) = self.score(storage, season, id)? {
+            self.ranking.remove(storage, (season, old, id.to_string()));
+            self.scores.remove(storage, (season, id.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Returns up to `limit` `(player, score)` pairs, highest score first, starting after the
+    /// given `(score, player)` position.
+    pub fn top(
+        &self,
+        storage: &dyn Storage,
+        season: u32,
+        start_after: Option<(i32, String)>,
+        limit: usize,
+    ) -> StdResult<Vec<(String, i32)>> {
+        let max = start_after.map(Bound::exclusive);
+        self.ranking
+            .sub_prefix(season)
+            .range(storage, None, max, Order::Descending)
+            .take(limit)
+            .map(|item| item.map(|((score, id), _)| (id, score)))
+            .collect()
+    }
+}